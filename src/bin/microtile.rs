@@ -18,12 +18,14 @@ use microtile_app as _; // global logger + panicking-behavior + memory layout
     dispatchers = [SWI0_EGU0, SWI1_EGU1]
 )]
 mod app {
-    use core::mem::MaybeUninit;
+    use core::{mem::MaybeUninit, sync::atomic::AtomicBool};
     use microbit::{
         display::nonblocking::{Display, Frame, MicrobitFrame},
+        gpio::BTN_A,
         hal::{
             gpiote::Gpiote,
             prelude::_embedded_hal_timer_CountDown,
+            rng::Rng,
             timer::{Instance, Periodic, Timer},
             uarte::Pins,
         },
@@ -42,18 +44,34 @@ mod app {
             },
             button::{GpioResources, RotationDriver, Started as RotationStarted},
             cli::{
-                downlink::DownlinkDriver, init as init_cli, receiver::CommandReceiver,
-                uplink::UplinkDriver, Resources as CliResources,
+                downlink::DownlinkDriver, init as init_cli, interrupt::UarteInterrupt,
+                mirror::Mirror, notifier::Notifier, receiver::CommandReceiver,
+                uplink::UplinkDriver, Resources as CliResources, Shared as CliShared,
+            },
+            display::{
+                tile_image, AnimationRenderer, GameOverSequence, CALIBRATION_IMAGE, PAUSE_IMAGE,
             },
-            display::GridRenderer,
             errata::clear_int_i2c_interrupt_line,
+            storage::{NvmcFlash, Storage},
             timer::{GameTickDriver, Started as TickStarted},
         },
         game::{
+            animation::Animation,
+            board::Board,
+            clock::PlayClock,
             driver::{GameDriver, MAILBOX_CAPACITY},
+            event::{Event, Listener},
+            highscore::SharedHighScores,
             message::Message,
-            tile::LoopingProducer,
+            record::SharedRecording,
+            shading::ShadedBoard,
+            snapshot::SharedSnapshot,
+            speed::Speed,
+            status::SharedStatus,
+            tile::{Lookahead, RandomProducer, TileKind},
+            tilt::Tilt,
         },
+        settings::{Settings, SharedSettings},
     };
     use microtile_engine::{gameplay::game::Observer, geometry::grid::Grid};
     use rtic_sync::channel::{Channel, TrySendError};
@@ -61,18 +79,139 @@ mod app {
     const HIGH_LEVEL_DISPLAY_FREQ: u32 = 6;
     const HIGH_LEVEL_DISPLAY_CYCLES: u32 =
         Timer::<HighLevelDisplayDriver, Periodic>::TICKS_PER_SECOND / HIGH_LEVEL_DISPLAY_FREQ;
+    // number of display toggles the upcoming tile is flashed for
+    const PREVIEW_STEPS: u8 = 2;
+    // number of upcoming tiles the game knows in advance
+    const LOOKAHEAD_DEPTH: usize = 1;
+
+    type Producer = Lookahead<RandomProducer, LOOKAHEAD_DEPTH>;
+
+    /// What the display currently shows.
+    enum Screen {
+        Game,
+        Paused,
+        Calibrating,
+        GameOver(GameOverSequence),
+    }
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone, Copy)]
     struct GameObserver;
 
+    impl GameObserver {
+        fn switch_screen(screen: Screen) {
+            match switch_screen::spawn(screen) {
+                Ok(()) => {}
+                Err(_) => {
+                    defmt::warn!("Dropping screen switch because the previous one is pending")
+                }
+            }
+        }
+
+        fn announce(event: Event) {
+            match announce::spawn(event) {
+                Ok(()) => {}
+                Err(_) => {
+                    defmt::warn!("Dropping announcement because the previous one is pending");
+                }
+            }
+        }
+    }
+
     impl Observer for GameObserver {
         fn signal_board_changed(&self, active: Grid, passive: Grid) {
             // When processing the topmost row, there are two signals comming in at short distance,
             // because processing the last row triggers a signal and placing a new tile triggers a signal too
-            match update_frames::spawn(active, passive) {
+            match update_board::spawn(active, passive) {
                 Ok(()) => {}
                 Err(_) => defmt::warn!("Dropping board update to allow for hardware to catch up"),
             }
+            // A pending redraw picks up the latest board anyway
+            mirror_board::spawn().ok();
+        }
+    }
+
+    impl Listener for GameObserver {
+        fn signal_event(&self, event: Event) {
+            match event {
+                Event::Paused => Self::switch_screen(Screen::Paused),
+                Event::Restored(_) => {
+                    Self::switch_screen(Screen::Paused);
+                    Self::announce(event);
+                }
+                Event::SnapshotsStopped => Self::announce(event),
+                Event::SnapshotTaken => match store_snapshot::spawn() {
+                    Ok(()) => {}
+                    Err(_) => {
+                        // The pending task picks up the latest snapshot anyway.
+                        defmt::debug!("Merging snapshot with the pending one");
+                    }
+                },
+                Event::Resumed | Event::GameStarted => Self::switch_screen(Screen::Game),
+                Event::GameOver { score, place } => {
+                    let mut sequence = GameOverSequence::new(score.points);
+                    if place.is_some() {
+                        sequence = sequence.with_high_score();
+                    }
+                    Self::switch_screen(Screen::GameOver(sequence));
+                    Self::announce(event);
+                    if place.is_some() {
+                        match store_high_scores::spawn() {
+                            Ok(()) => {}
+                            Err(_) => {
+                                defmt::warn!(
+                                    "Dropping high scores because the previous ones are pending"
+                                );
+                            }
+                        }
+                    }
+                }
+                Event::CalibrationStarted => {
+                    Self::switch_screen(Screen::Calibrating);
+                    Self::announce(event);
+                }
+                Event::Calibrated { x, z } => {
+                    Self::switch_screen(Screen::Paused);
+                    Self::announce(event);
+                    match store_calibration::spawn(x, z) {
+                        Ok(()) => {}
+                        Err(_) => {
+                            defmt::warn!(
+                                "Dropping calibration because the previous one is pending"
+                            );
+                        }
+                    }
+                }
+                Event::CalibrationFailed => {
+                    Self::switch_screen(Screen::Paused);
+                    Self::announce(event);
+                }
+                Event::LevelChanged(level) => match update_level::spawn(level) {
+                    Ok(()) => {}
+                    Err(_) => {
+                        defmt::warn!("Dropping level update because the previous one is pending");
+                    }
+                },
+                Event::SpeedChanged(speed) => match update_speed::spawn(speed) {
+                    Ok(()) => {}
+                    Err(_) => {
+                        defmt::warn!("Dropping speed update because the previous one is pending");
+                    }
+                },
+                Event::NextTile(tile) => match show_preview::spawn(tile) {
+                    Ok(()) => {}
+                    Err(_) => {
+                        defmt::warn!("Dropping preview because the previous one is pending");
+                    }
+                },
+                Event::TileLanded { tile, board } => {
+                    match play_animation::spawn(Animation::landing(tile, board)) {
+                        Ok(()) => {}
+                        Err(_) => {
+                            defmt::warn!("Dropping animation because the previous one is pending");
+                        }
+                    }
+                }
+            }
         }
     }
 
@@ -80,16 +219,22 @@ mod app {
     #[shared]
     struct Shared {
         display: Display<LowLevelDisplayDriver>,
-        merged_frame: MicrobitFrame,
-        passive_frame: MicrobitFrame,
+        // the falling tile and the settled cells
+        board: (Board, Board),
+        screen: Screen,
+        // upcoming tile to flash and the remaining number of display toggles to do so
+        preview: Option<(TileKind, u8)>,
+        // shown instead of the board (and the preview) until done
+        animation: Option<Animation>,
+        timer_handler: &'static mut GameTickDriver<'static, TimerGameDriver, TickStarted>,
     }
 
     // Local resources go here
     #[local]
     struct Local {
         highlevel_display_driver: Timer<HighLevelDisplayDriver, Periodic>,
-        game_driver: &'static mut GameDriver<'static, GameObserver, LoopingProducer>,
-        timer_handler: &'static mut GameTickDriver<'static, TimerGameDriver, TickStarted>,
+        frame: MicrobitFrame,
+        game_driver: &'static mut GameDriver<'static, GameObserver, Producer>,
         rotation_handler: &'static mut RotationDriver<'static, 'static, RotationStarted>,
         horizontal_handler: &'static mut HorizontalMovementDriver<
             'static,
@@ -99,12 +244,31 @@ mod app {
         >,
         downlink_driver: &'static mut DownlinkDriver<CliDriver>,
         uplink_driver: &'static mut UplinkDriver<CliDriver>,
+        uarte_interrupt: UarteInterrupt<CliDriver>,
         command_driver: &'static mut CommandReceiver,
+        notifier: &'static mut Notifier,
+        mirror: Mirror,
+        settings: &'static SharedSettings,
+        storage: &'static Storage,
+        high_scores: &'static SharedHighScores,
+        // the same storage, as local resources cannot be shared between tasks
+        high_score_storage: &'static Storage,
+        snapshot: &'static SharedSnapshot,
+        snapshot_storage: &'static Storage,
+        display_settings: &'static SharedSettings,
     }
 
     #[init(local = [
         game_driver_channel: Channel<Message, MAILBOX_CAPACITY> = Channel::new(),
-        game_driver_mem: MaybeUninit<GameDriver<'static, GameObserver, LoopingProducer>> = MaybeUninit::uninit(),
+        game_status: SharedStatus = SharedStatus::new(),
+        game_recording: SharedRecording = SharedRecording::new(),
+        game_settings: SharedSettings = SharedSettings::new(Settings::DEFAULT),
+        game_high_scores: SharedHighScores = SharedHighScores::new(),
+        play_clock: PlayClock = PlayClock::new(),
+        game_snapshot: SharedSnapshot = SharedSnapshot::new(),
+        storage_mem: MaybeUninit<Storage> = MaybeUninit::uninit(),
+        game_driver_mem: MaybeUninit<GameDriver<'static, GameObserver, Producer>> = MaybeUninit::uninit(),
+        button_a_mem: MaybeUninit<BTN_A> = MaybeUninit::uninit(),
         timer_handler_mem: MaybeUninit<GameTickDriver<'static, TimerGameDriver, TickStarted>> = MaybeUninit::uninit(),
         gpiote_mem: MaybeUninit<Gpiote> = MaybeUninit::uninit(),
         rotation_resources_mem: MaybeUninit<GpioResources<'static>> = MaybeUninit::uninit(),
@@ -112,9 +276,11 @@ mod app {
         horizontal_resources_mem: MaybeUninit<HorizontalIrqResources<'static>> = MaybeUninit::uninit(),
         horizontal_handler_mem: MaybeUninit<HorizontalMovementDriver<'static, 'static, HorizontalDriver, HorizontalStarted>> = MaybeUninit::uninit(),
         cli_resources_mem: MaybeUninit<CliResources> = MaybeUninit::uninit(),
+        cli_mirror_enabled: AtomicBool = AtomicBool::new(false),
         uplink_driver_mem: MaybeUninit<UplinkDriver<CliDriver>> = MaybeUninit::uninit(),
         downlink_driver_mem: MaybeUninit<DownlinkDriver<CliDriver>> = MaybeUninit::uninit(),
         command_receiver_mem: MaybeUninit<CommandReceiver> = MaybeUninit::uninit(),
+        notifier_mem: MaybeUninit<Notifier> = MaybeUninit::uninit(),
     ])]
     fn init(cx: init::Context) -> (Shared, Local) {
         defmt::trace!("microtile_app::init()");
@@ -134,14 +300,64 @@ mod app {
             clear_int_i2c_interrupt_line(board.TWIM0, board.i2c_internal, &mut delay);
         defmt::info!("Done taking care of errata.");
 
+        let (mut sender, receiver) = cx.local.game_driver_channel.split();
+        let status: &'static SharedStatus = cx.local.game_status;
+        let recording: &'static SharedRecording = cx.local.game_recording;
+
+        // Restore the settings saved last. Without any, fall back to the defaults, keeping the
+        // calibration stored by earlier firmware.
+        let mut flash = NvmcFlash::new(board.NVMC);
+        let legacy_calibration = flash.legacy_calibration();
+        let storage: &'static Storage = cx
+            .local
+            .storage_mem
+            .write(Storage::open(flash).expect("reading the flash should always be valid"));
+        let saved = storage.settings();
+        if saved.is_some() {
+            defmt::info!("Restored the saved settings.");
+        }
+        let restored = saved.unwrap_or(Settings {
+            calibration: legacy_calibration,
+            ..Settings::DEFAULT
+        });
+        let settings: &'static SharedSettings = cx.local.game_settings;
+        settings.update(|settings| *settings = restored);
+
+        let high_scores: &'static SharedHighScores = cx.local.game_high_scores;
+        if let Some(saved) = storage.high_scores() {
+            defmt::info!("Restored {} high scores.", saved.entries().len());
+            high_scores.update(|high_scores| *high_scores = saved);
+        }
+        let clock: &'static PlayClock = cx.local.play_clock;
+
+        // The game saved before the reset, if any, is offered for resumption once running.
+        let snapshot: &'static SharedSnapshot = cx.local.game_snapshot;
+        if let Some(saved) = storage.snapshot() {
+            defmt::info!("Found a game saved before the reset.");
+            snapshot.update(|snapshot| *snapshot = Some(saved));
+        }
+
         // Setup commandline interface
         let cli_resources = cx.local.cli_resources_mem.write(CliResources::default());
-        let (uplink, downlink, command_recv) =
-            init_cli(board.UARTE0, Pins::from(board.uart), cli_resources)
-                .expect("Could not initialize CLI drivers");
+        let (uplink, downlink, command_recv, notifier, mirror, uarte_interrupt) = init_cli(
+            board.UARTE0,
+            Pins::from(board.uart),
+            cli_resources,
+            sender.clone(),
+            CliShared {
+                status,
+                recording,
+                settings,
+                high_scores,
+                storage,
+                mirror_enabled: cx.local.cli_mirror_enabled,
+            },
+        )
+        .expect("Could not initialize CLI drivers");
         let uplink = cx.local.uplink_driver_mem.write(uplink);
         let downlink = cx.local.downlink_driver_mem.write(downlink);
         let command_recv = cx.local.command_receiver_mem.write(command_recv);
+        let notifier = cx.local.notifier_mem.write(notifier);
 
         drive_cli_downlink::spawn().expect("Failed to spawn downlink driver task");
         drive_cli_uplink::spawn().expect("Failed to spawn uplink driver task");
@@ -149,8 +365,6 @@ mod app {
 
         let observer = GameObserver {};
 
-        let (sender, receiver) = cx.local.game_driver_channel.split();
-
         cx.local.gpiote_mem.write(Gpiote::new(board.GPIOTE));
         let gpiote = unsafe { cx.local.gpiote_mem.assume_init_mut() };
 
@@ -167,15 +381,48 @@ mod app {
         );
         let horizontal_handler = unsafe { cx.local.horizontal_handler_mem.assume_init_mut() };
 
-        cx.local.game_driver_mem.write(GameDriver::new(
-            receiver,
-            observer,
-            LoopingProducer::default(),
-        ));
+        // Every boot plays a different sequence of tiles, unless the seed is fixed at build time
+        // (e.g. `MICROTILE_SEED=42 cargo run`) or by the `game.seed` setting to replay a game.
+        let seed = match option_env!("MICROTILE_SEED") {
+            Some(seed) => seed.parse().expect("MICROTILE_SEED should be a u32"),
+            None if restored.seed != 0 => restored.seed,
+            None => Rng::new(board.RNG).random_u32(),
+        };
+        defmt::info!("Seeding the tile sequence with {}.", seed);
+
+        // Restore the neutral position from the last calibration, if any
+        let mut tilt = Tilt::with_settings(restored.tilt);
+        if let Some((x, z)) = restored.calibration {
+            if tilt.calibrate(x, z) {
+                defmt::info!("Restored the calibrated neutral position ({}, {}).", x, z);
+            } else {
+                defmt::warn!("Ignoring the stored calibration, it is implausible.");
+            }
+        }
+
+        cx.local.game_driver_mem.write(
+            GameDriver::new(
+                receiver,
+                observer,
+                Producer::new(RandomProducer::new(seed)),
+                status,
+            )
+            .with_recording(recording)
+            .with_tilt(tilt)
+            .with_clock(clock)
+            .with_high_scores(high_scores)
+            .with_snapshots(snapshot),
+        );
         let game_driver = unsafe { cx.local.game_driver_mem.assume_init_mut() };
 
+        // Button A is shared between the tick driver (accelerating the game) and the rotation
+        // driver (acting as modifier for toggling the pause)
+        let button_a: &'static BTN_A = cx.local.button_a_mem.write(board.buttons.button_a);
+
         cx.local.timer_handler_mem.write(
-            GameTickDriver::new(sender.clone(), board.buttons.button_a, delay.free()).start(),
+            GameTickDriver::new(sender.clone(), button_a, delay.free())
+                .with_clock(clock)
+                .start(),
         );
         let timer_handler = unsafe { cx.local.timer_handler_mem.assume_init_mut() };
 
@@ -188,14 +435,17 @@ mod app {
 
         cx.local
             .rotation_handler_mem
-            .write(RotationDriver::new(rotation_resources, sender.clone()).start());
+            .write(RotationDriver::new(rotation_resources, button_a, sender.clone()).start());
         let rotation_handler = unsafe { cx.local.rotation_handler_mem.assume_init_mut() };
 
+        // The game announces the speed to the tick driver once running.
+        if restored.speed != Speed::DEFAULT {
+            sender
+                .try_send(Message::SetSpeed(restored.speed))
+                .expect("the game's mailbox should be empty at boot");
+        }
         drive_game::spawn().ok();
 
-        let passive_frame = MicrobitFrame::default();
-        let merged_frame = MicrobitFrame::default();
-
         // Configure timer to generate an IRQ at frequency HIGH_LEVEL_DISPLAY_FREQ
         let mut highlevel_display = Timer::periodic(board.TIMER1);
         highlevel_display.enable_interrupt();
@@ -210,29 +460,43 @@ mod app {
         (
             Shared {
                 display: Display::new(board.TIMER0, board.display_pins),
-                merged_frame,
-                passive_frame,
+                board: (Board::empty(), Board::empty()),
+                screen: Screen::Game,
+                preview: None,
+                animation: None,
+                timer_handler,
             },
             Local {
                 highlevel_display_driver: highlevel_display,
+                frame: MicrobitFrame::default(),
                 game_driver,
-                timer_handler,
                 rotation_handler,
                 horizontal_handler,
                 downlink_driver: downlink,
                 uplink_driver: uplink,
+                uarte_interrupt,
                 command_driver: command_recv,
+                notifier,
+                mirror,
+                settings,
+                storage,
+                high_scores,
+                high_score_storage: storage,
+                snapshot,
+                snapshot_storage: storage,
+                display_settings: settings,
             },
         )
     }
 
-    // Optional idle, can be removed if not needed.
     #[idle]
     fn idle(_: idle::Context) -> ! {
         defmt::trace!("microtile_app::idle()");
 
-        #[allow(clippy::empty_loop)]
-        loop {}
+        // All the work is done in interrupts, so sleep until the next one.
+        loop {
+            cortex_m::asm::wfi();
+        }
     }
 
     #[task(binds = TIMER1, priority = 4, local = [ highlevel_display_driver ])]
@@ -252,17 +516,62 @@ mod app {
         };
     }
 
-    #[task(priority = 1, local = [ next_frame_passive: bool = false ], shared = [ display, passive_frame, merged_frame ])]
-    async fn display_toggle_frame(cx: display_toggle_frame::Context) {
+    #[task(priority = 1, local = [ frame, display_settings, falling_tile_lit: bool = true ], shared = [ display, board, screen, preview, animation ])]
+    async fn display_toggle_frame(mut cx: display_toggle_frame::Context) {
         defmt::trace!("microtile_app::display_toggle_frame()");
-        if *cx.local.next_frame_passive {
-            (cx.shared.display, cx.shared.passive_frame)
-                .lock(|display, frame| display.show_frame(frame));
-        } else {
-            (cx.shared.display, cx.shared.merged_frame)
-                .lock(|display, frame| display.show_frame(frame));
+        let frame = &mut *cx.local.frame;
+        let shading = cx.local.display_settings.get().shading;
+        let lit = *cx.local.falling_tile_lit;
+        let board = &mut cx.shared.board;
+        let preview = &mut cx.shared.preview;
+        let animation = &mut cx.shared.animation;
+        let shows_game = cx.shared.screen.lock(|screen| match screen {
+            Screen::Game => animation.lock(|animation| match animation {
+                Some(playing) => {
+                    let live = board.lock(|(active, passive)| {
+                        ShadedBoard::new(*active, *passive, shading, lit)
+                    });
+                    frame.set(&AnimationRenderer::new(playing, live));
+                    playing.advance();
+                    if playing.is_done() {
+                        *animation = None;
+                    }
+                    false
+                }
+                None => preview.lock(|preview| match preview {
+                    Some((tile, steps)) => {
+                        frame.set(tile_image(*tile));
+                        *steps -= 1;
+                        if *steps == 0 {
+                            *preview = None;
+                        }
+                        false
+                    }
+                    None => true,
+                }),
+            }),
+            Screen::Paused => {
+                frame.set(&PAUSE_IMAGE);
+                false
+            }
+            Screen::Calibrating => {
+                frame.set(&CALIBRATION_IMAGE);
+                false
+            }
+            Screen::GameOver(sequence) => {
+                frame.set(sequence);
+                sequence.advance();
+                false
+            }
+        });
+
+        if shows_game {
+            board.lock(|(active, passive)| {
+                frame.set(&ShadedBoard::new(*active, *passive, shading, lit));
+            });
         }
-        *cx.local.next_frame_passive = !*cx.local.next_frame_passive;
+        cx.shared.display.lock(|display| display.show_frame(frame));
+        *cx.local.falling_tile_lit = !*cx.local.falling_tile_lit;
     }
 
     #[task(binds = TIMER0, priority = 4, shared = [ display ])]
@@ -272,6 +581,12 @@ mod app {
         });
     }
 
+    #[task(binds = UARTE0_UART0, priority = 3, local = [ uarte_interrupt ])]
+    fn drive_cli_interrupt(cx: drive_cli_interrupt::Context) {
+        defmt::trace!("microtile_app::drive_cli_interrupt()");
+        cx.local.uarte_interrupt.handle_uarte_event();
+    }
+
     #[task(priority = 1, local = [ downlink_driver ])]
     async fn drive_cli_downlink(cx: drive_cli_downlink::Context) {
         defmt::trace!("microtile_app::drive_cli_downlink()");
@@ -308,10 +623,14 @@ mod app {
         let _ = cx.local.game_driver.run().await;
     }
 
-    #[task(binds = TIMER2, priority = 4, local = [ timer_handler ])]
-    fn tick_game(cx: tick_game::Context) {
+    #[task(binds = TIMER2, priority = 4, shared = [ timer_handler ])]
+    fn tick_game(mut cx: tick_game::Context) {
         defmt::trace!("microtile_app::tick_game()");
-        match cx.local.timer_handler.handle_timer_event() {
+        match cx
+            .shared
+            .timer_handler
+            .lock(|timer_handler| timer_handler.handle_timer_event())
+        {
             Ok(()) => {}
             Err(TrySendError::Full(_)) => {
                 defmt::warn!("Dropping a game tick to allow the engine to catch up.");
@@ -320,13 +639,102 @@ mod app {
         };
     }
 
-    #[task(priority = 2, shared = [ merged_frame, passive_frame ])]
-    async fn update_frames(cx: update_frames::Context, active: Grid, passive: Grid) {
-        defmt::trace!("microtile_app::update_frames()");
-        (cx.shared.merged_frame, cx.shared.passive_frame).lock(|merged_frame, passive_frame| {
-            passive_frame.set(&GridRenderer::new(&passive));
-            merged_frame.set(&GridRenderer::new(&active.union(&passive)));
-        });
+    #[task(priority = 2, shared = [ board ])]
+    async fn update_board(mut cx: update_board::Context, active: Grid, passive: Grid) {
+        defmt::trace!("microtile_app::update_board()");
+        let board = (Board::from(&active), Board::from(&passive));
+        cx.shared.board.lock(|b| *b = board);
+    }
+
+    #[task(priority = 2, shared = [ screen, animation ])]
+    async fn switch_screen(mut cx: switch_screen::Context, screen: Screen) {
+        defmt::trace!("microtile_app::switch_screen()");
+        cx.shared.screen.lock(|s| *s = screen);
+        // The board frozen by the animation is outdated once the game screen shows again.
+        cx.shared.animation.lock(|a| *a = None);
+    }
+
+    #[task(priority = 2, shared = [ preview ])]
+    async fn show_preview(mut cx: show_preview::Context, tile: TileKind) {
+        defmt::trace!("microtile_app::show_preview()");
+        cx.shared.preview.lock(|p| *p = Some((tile, PREVIEW_STEPS)));
+    }
+
+    #[task(priority = 2, shared = [ animation ])]
+    async fn play_animation(mut cx: play_animation::Context, animation: Animation) {
+        defmt::trace!("microtile_app::play_animation()");
+        cx.shared.animation.lock(|a| *a = Some(animation));
+    }
+
+    #[task(priority = 1, local = [ settings, storage ])]
+    async fn store_calibration(cx: store_calibration::Context, x: i16, z: i16) {
+        defmt::trace!("microtile_app::store_calibration()");
+        cx.local
+            .settings
+            .update(|settings| settings.calibration = Some((x, z)));
+        // Only the calibration is saved right away, the other settings are up to the `save` command.
+        let saved = cx
+            .local
+            .storage
+            .update_settings(|settings| settings.calibration = Some((x, z)));
+        if saved.is_err() {
+            defmt::warn!("Failed to store the calibration, it will be lost on reset");
+        }
+    }
+
+    #[task(priority = 1, local = [ high_scores, high_score_storage ])]
+    async fn store_high_scores(cx: store_high_scores::Context) {
+        defmt::trace!("microtile_app::store_high_scores()");
+        let high_scores = cx.local.high_scores.read(Clone::clone);
+        if cx
+            .local
+            .high_score_storage
+            .save_high_scores(&high_scores)
+            .is_err()
+        {
+            defmt::warn!("Failed to store the high scores, the new one will be lost on reset");
+        }
+    }
+
+    #[task(priority = 1, local = [ snapshot, snapshot_storage ])]
+    async fn store_snapshot(cx: store_snapshot::Context) {
+        defmt::trace!("microtile_app::store_snapshot()");
+        let snapshot = cx.local.snapshot.read(Clone::clone);
+        if cx.local.snapshot_storage.save_snapshot(&snapshot).is_err() {
+            defmt::warn!("Failed to store the snapshot, the game cannot be resumed after a reset");
+        }
+    }
+
+    #[task(priority = 1, local = [ notifier ])]
+    async fn announce(cx: announce::Context, event: Event) {
+        defmt::trace!("microtile_app::announce()");
+        cx.local
+            .notifier
+            .notify(event)
+            .await
+            .expect("Error while announcing game event");
+    }
+
+    #[task(priority = 1, local = [ mirror ])]
+    async fn mirror_board(cx: mirror_board::Context) {
+        defmt::trace!("microtile_app::mirror_board()");
+        cx.local.mirror.refresh();
+    }
+
+    #[task(priority = 2, shared = [ timer_handler ])]
+    async fn update_level(mut cx: update_level::Context, level: u8) {
+        defmt::trace!("microtile_app::update_level()");
+        cx.shared
+            .timer_handler
+            .lock(|timer_handler| timer_handler.set_level(level));
+    }
+
+    #[task(priority = 2, shared = [ timer_handler ])]
+    async fn update_speed(mut cx: update_speed::Context, speed: Speed) {
+        defmt::trace!("microtile_app::update_speed()");
+        cx.shared
+            .timer_handler
+            .lock(|timer_handler| timer_handler.set_speed(speed));
     }
 
     #[task(binds = GPIOTE, priority = 4, local = [ rotation_handler, horizontal_handler ])]
@@ -0,0 +1,358 @@
+//! Terminal simulator running the game on the host.
+//!
+//! The simulator drives the very same [`GameDriver`] the micro:bit runs, but replaces the hardware
+//! with the keyboard and the terminal:
+//!
+//! - `←`/`→` tilt the (virtual) board, i.e. send synthetic accelerometer data
+//! - `↑`/`space` rotate the tile or start a new game once over, like pressing button B
+//! - `↓` ticks the game right away, like holding button A
+//! - `p` pauses and resumes the game, like pressing button B while holding button A
+//! - `r` restarts the current game and replays its input
+//! - `c` calibrates the tilt to the current (virtual) inclination, like the `calibrate` command
+//! - `q`/`esc` quit
+//!
+//! Run it via `cargo sim` (see `.cargo/config.toml`). The tiles are random, pass `--seed <n>` (e.g.
+//! `cargo sim -- --seed 42`) to replay a game.
+
+use core::{future::Future, pin::pin, task::Context, time::Duration};
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event as TerminalEvent, KeyCode, KeyEventKind},
+    execute, queue,
+    style::Print,
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use futures::task::noop_waker_ref;
+use microtile_app::game::{
+    clock::PlayClock,
+    driver::{GameDriver, MAILBOX_CAPACITY},
+    event::{Event, Listener},
+    message::Message,
+    record::SharedRecording,
+    speed::Speed,
+    status::SharedStatus,
+    tile::{Lookahead, RandomProducer, TileKind},
+    tilt::{tilt_towards, Tilt},
+};
+use microtile_engine::{gameplay::game::Observer, geometry::grid::Grid};
+use rtic_sync::channel::{Channel, Sender, TrySendError};
+use std::{
+    env,
+    io::{stdout, Result as IoResult, Write},
+    sync::{Arc, Mutex},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+// Same timing as on the micro:bit, see `microtile.rs` and `device::timer`
+const DISPLAY_TOGGLE_PERIOD: Duration = Duration::from_millis(1000 / 6);
+const ACCEL_PERIOD: Duration = Duration::from_millis(1000 / 25);
+
+fn tick_period(level: u8) -> Duration {
+    Duration::from_micros(Speed::DEFAULT.period_us(level).into())
+}
+
+const ROWS: usize = 5;
+const COLUMNS: usize = 5;
+const MAX_COLUMN: u8 = 4;
+
+type Frame = [[bool; COLUMNS]; ROWS];
+
+fn to_frame(grid: &Grid) -> Frame {
+    let mut frame = Frame::default();
+    for (row, cells) in frame.iter_mut().enumerate() {
+        for (column, cell) in cells.iter_mut().enumerate() {
+            *cell = grid.is_element_set(row, column).unwrap_or(false);
+        }
+    }
+    frame
+}
+
+/// Counterpart to the `update_board`, `switch_screen` and `update_level` tasks of the micro:bit
+/// application.
+#[derive(Debug, Clone, Default)]
+struct Frames {
+    frames: Arc<Mutex<(Frame, Frame)>>,
+    paused: Arc<Mutex<bool>>,
+    over: Arc<Mutex<bool>>,
+    calibrating: Arc<Mutex<bool>>,
+    level: Arc<Mutex<u8>>,
+}
+
+impl Frames {
+    fn paused(&self) -> bool {
+        *self.paused.lock().expect("lock should not be poisoned")
+    }
+
+    fn over(&self) -> bool {
+        *self.over.lock().expect("lock should not be poisoned")
+    }
+
+    fn calibrating(&self) -> bool {
+        *self
+            .calibrating
+            .lock()
+            .expect("lock should not be poisoned")
+    }
+
+    fn level(&self) -> u8 {
+        *self.level.lock().expect("lock should not be poisoned")
+    }
+
+    fn merged(&self) -> Frame {
+        self.frames.lock().expect("lock should not be poisoned").0
+    }
+
+    fn passive(&self) -> Frame {
+        self.frames.lock().expect("lock should not be poisoned").1
+    }
+}
+
+impl Observer for Frames {
+    fn signal_board_changed(&self, active: Grid, passive: Grid) {
+        *self.frames.lock().expect("lock should not be poisoned") =
+            (to_frame(&active.union(&passive)), to_frame(&passive));
+    }
+}
+
+impl Listener for Frames {
+    fn signal_event(&self, event: Event) {
+        match event {
+            Event::Paused | Event::Resumed => {
+                *self.paused.lock().expect("lock should not be poisoned") = event == Event::Paused;
+            }
+            Event::LevelChanged(level) => {
+                *self.level.lock().expect("lock should not be poisoned") = level;
+            }
+            // the upcoming tile is read from the status instead
+            Event::NextTile(_) => {}
+            // the cells are either on or off in the terminal, too coarse for animations
+            Event::TileLanded { .. } => {}
+            Event::GameOver { .. } => {
+                *self.over.lock().expect("lock should not be poisoned") = true
+            }
+            Event::GameStarted => {
+                *self.over.lock().expect("lock should not be poisoned") = false;
+                *self.paused.lock().expect("lock should not be poisoned") = false;
+            }
+            // there is no CLI to change the speed in the simulator
+            Event::SpeedChanged(_) => {}
+            // the simulator never saves a snapshot, hence never restores one either
+            Event::SnapshotTaken | Event::SnapshotsStopped | Event::Restored(_) => {}
+            // calibrating implies being paused
+            Event::CalibrationStarted => {
+                *self.paused.lock().expect("lock should not be poisoned") = true;
+                *self
+                    .calibrating
+                    .lock()
+                    .expect("lock should not be poisoned") = true;
+            }
+            Event::Calibrated { .. } | Event::CalibrationFailed => {
+                *self
+                    .calibrating
+                    .lock()
+                    .expect("lock should not be poisoned") = false;
+            }
+        }
+    }
+}
+
+fn send(sender: &mut Sender<'_, Message, MAILBOX_CAPACITY>, msg: Message) {
+    match sender.try_send(msg) {
+        Ok(()) | Err(TrySendError::Full(_)) => {}
+        Err(TrySendError::NoReceiver(_)) => unreachable!(),
+    }
+}
+
+// Same as `device::display::PAUSE_IMAGE`
+const PAUSE_FRAME: Frame = [
+    [false, false, false, false, false],
+    [false, true, false, true, false],
+    [false, true, false, true, false],
+    [false, true, false, true, false],
+    [false, false, false, false, false],
+];
+
+// Closed curtain of `device::display::GameOverSequence`, the score is printed anyway
+const GAME_OVER_FRAME: Frame = [[true; COLUMNS]; ROWS];
+
+/// Seed passed via `--seed <n>`, or else one derived from the current time.
+fn seed() -> u32 {
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--seed" {
+            return args
+                .next()
+                .and_then(|seed| seed.parse().ok())
+                .expect("--seed should be followed by a u32");
+        }
+    }
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time should be after the epoch")
+        .subsec_nanos()
+}
+
+fn render(
+    out: &mut impl Write,
+    frame: &Frame,
+    status: &SharedStatus,
+    banner: &str,
+) -> IoResult<()> {
+    let status = status.get();
+    let score = status.score;
+    queue!(
+        out,
+        MoveTo(0, 0),
+        Print(format!(
+            "microtile simulator (seed {:<10})\r\n\r\n\
+            score {:>6}  level {}  next {:<8}  {banner:<40}\r\n\r\n",
+            status.seed.unwrap_or_default(),
+            score.points,
+            score.level,
+            status.next.map_or("?", TileKind::name),
+        ))
+    )?;
+    // Row 0 is the bottom row, so print from top to bottom
+    for cells in frame.iter().rev() {
+        for cell in cells {
+            queue!(out, Print(if *cell { "██" } else { "··" }))?;
+        }
+        queue!(out, Print("\r\n"))?;
+    }
+    queue!(
+        out,
+        Print("\r\n←/→ tilt, ↑/space rotate, ↓ drop, p pause, r replay, c calibrate, q quit\r\n")
+    )?;
+    out.flush()
+}
+
+/// Restores the terminal on drop, even when unwinding.
+struct Terminal;
+
+impl Terminal {
+    fn setup() -> IoResult<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(stdout(), EnterAlternateScreen, Hide, Clear(ClearType::All))?;
+        Ok(Self)
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let _ = execute!(stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+fn main() -> IoResult<()> {
+    let mut channel = Channel::<Message, MAILBOX_CAPACITY>::new();
+    let (mut sender, receiver) = channel.split();
+    let frames = Frames::default();
+    let status = SharedStatus::new();
+    let recording = SharedRecording::new();
+    let clock = PlayClock::new();
+    let mut driver = GameDriver::new(
+        receiver,
+        frames.clone(),
+        Lookahead::<_, 1>::new(RandomProducer::new(seed())),
+        &status,
+    )
+    .with_recording(&recording)
+    .with_clock(&clock)
+    // The keyboard does not jitter, so there is no need to smooth the synthetic samples.
+    .with_tilt(Tilt::new(1.0, 0.0));
+
+    // The driver runs forever, so instead of handing it to an executor, we poll it by hand
+    // whenever there is a new message.
+    let mut run = pin!(driver.run());
+    let mut cx = Context::from_waker(noop_waker_ref());
+    let mut drive = move || {
+        assert!(
+            run.as_mut().poll(&mut cx).is_pending(),
+            "game driver should not terminate"
+        );
+    };
+    drive();
+
+    let _terminal = Terminal::setup()?;
+    let mut out = stdout();
+
+    let mut column = MAX_COLUMN / 2;
+    let mut show_passive = false;
+    let mut next_tick = Instant::now() + tick_period(0);
+    let mut next_toggle = Instant::now();
+    let mut next_sample = Instant::now();
+    let mut last = Instant::now();
+
+    loop {
+        let now = Instant::now();
+        // on the micro:bit, the tick timer advances the clock
+        clock.advance(u32::try_from((now - last).as_micros()).unwrap_or(u32::MAX));
+        last = now;
+        if now >= next_tick {
+            send(&mut sender, Message::TimerTick);
+            next_tick += tick_period(frames.level());
+        }
+        // The keyboard only yields samples on key presses, so keep the calibration fed like the
+        // accelerometer would.
+        let calibrating = frames.calibrating();
+        if calibrating && now >= next_sample {
+            send(&mut sender, tilt_towards(column));
+            next_sample = now + ACCEL_PERIOD;
+        }
+        if now >= next_toggle {
+            let over = frames.over();
+            let frame = if over {
+                GAME_OVER_FRAME
+            } else if frames.paused() {
+                PAUSE_FRAME
+            } else if show_passive {
+                frames.passive()
+            } else {
+                frames.merged()
+            };
+            let banner = if over {
+                "GAME OVER - press ↑/space to play again"
+            } else if calibrating {
+                "CALIBRATING - hold the board still"
+            } else {
+                ""
+            };
+            render(&mut out, &frame, &status, banner)?;
+            show_passive = !show_passive;
+            next_toggle += DISPLAY_TOGGLE_PERIOD;
+        }
+
+        let mut deadline = next_tick.min(next_toggle);
+        if calibrating {
+            deadline = deadline.min(next_sample);
+        }
+        let timeout = deadline.saturating_duration_since(now);
+        if event::poll(timeout)? {
+            if let TerminalEvent::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Left => {
+                        column = column.saturating_sub(1);
+                        send(&mut sender, tilt_towards(column));
+                    }
+                    KeyCode::Right => {
+                        column = (column + 1).min(MAX_COLUMN);
+                        send(&mut sender, tilt_towards(column));
+                    }
+                    KeyCode::Up | KeyCode::Char(' ') => send(&mut sender, Message::BtnBPress),
+                    KeyCode::Down => send(&mut sender, Message::TimerTick),
+                    KeyCode::Char('p') => send(&mut sender, Message::TogglePause),
+                    KeyCode::Char('r') => send(&mut sender, Message::Replay),
+                    KeyCode::Char('c') => send(&mut sender, Message::Calibrate),
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    _ => {}
+                }
+            }
+        }
+
+        drive();
+    }
+}
@@ -0,0 +1,266 @@
+use super::parse::{Argument, Arguments, Keyword};
+use crate::{
+    game::{board::Board, record::Entry, shading::DisplayStyle, snapshot::Step, speed::Speed},
+    settings::Key,
+};
+use core::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    str,
+};
+use heapless::String;
+
+/// The offending input a [`CommandError`] refers to, truncated to [`Token::CAPACITY`] bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token(String<{ Token::CAPACITY }>);
+
+impl Token {
+    pub const CAPACITY: usize = 16;
+    const ELLIPSIS: &'static str = "..";
+
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for Token {
+    fn from(value: &str) -> Self {
+        let mut token = String::new();
+        if value.len() <= Self::CAPACITY {
+            // cannot fail, the value fits
+            let _ = token.push_str(value);
+        } else {
+            for c in value.chars() {
+                if token.len() + c.len_utf8() + Self::ELLIPSIS.len() > Self::CAPACITY {
+                    break;
+                }
+                let _ = token.push(c);
+            }
+            let _ = token.push_str(Self::ELLIPSIS);
+        }
+        Self(token)
+    }
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "'{}'", self.0)
+    }
+}
+
+/// Reasons for a line not to be a valid [`Command`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandError {
+    /// The line is not valid UTF-8.
+    InvalidEncoding,
+    /// The line's first token does not name a command.
+    UnknownCommand(Token),
+    /// The command takes more arguments than given.
+    MissingArgument,
+    /// The command takes fewer arguments than given, carrying the first surplus one.
+    UnexpectedArgument(Token),
+    /// An argument is not a number or out of range.
+    InvalidNumber(Token),
+    /// An argument is not one of the accepted keywords.
+    InvalidKeyword(Token),
+    /// An argument is not of the form `key=value`.
+    InvalidAssignment(Token),
+}
+
+impl Display for CommandError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::InvalidEncoding => write!(f, "invalid characters"),
+            Self::UnknownCommand(token) => write!(f, "unknown command {token}"),
+            Self::MissingArgument => write!(f, "missing argument"),
+            Self::UnexpectedArgument(token) => write!(f, "unexpected argument {token}"),
+            Self::InvalidNumber(token) => write!(f, "invalid number {token}"),
+            Self::InvalidKeyword(token) => write!(f, "invalid keyword {token}"),
+            Self::InvalidAssignment(token) => write!(f, "expected key=value, got {token}"),
+        }
+    }
+}
+
+/// Argument turning a feature on or off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Switch {
+    On,
+    Off,
+}
+
+impl Keyword for Switch {
+    const KEYWORDS: &'static [(&'static str, Self)] = &[("on", Self::On), ("off", Self::Off)];
+}
+
+/// What to do with the high scores.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scores {
+    List,
+    Clear,
+}
+
+impl Keyword for Scores {
+    const KEYWORDS: &'static [(&'static str, Self)] =
+        &[("list", Self::List), ("clear", Self::Clear)];
+}
+
+impl Keyword for Key {
+    const KEYWORDS: &'static [(&'static str, Self)] = &[
+        (Key::SpeedPeriod.name(), Key::SpeedPeriod),
+        (Key::SpeedDrop.name(), Key::SpeedDrop),
+        (Key::TiltHysteresis.name(), Key::TiltHysteresis),
+        (Key::TiltSmoothing.name(), Key::TiltSmoothing),
+        (Key::Seed.name(), Key::Seed),
+        (Key::SettledBrightness.name(), Key::SettledBrightness),
+        (Key::FallingBrightness.name(), Key::FallingBrightness),
+    ];
+}
+
+impl Keyword for DisplayStyle {
+    const KEYWORDS: &'static [(&'static str, Self)] = &[
+        (DisplayStyle::Blink.name(), DisplayStyle::Blink),
+        (DisplayStyle::Dim.name(), DisplayStyle::Dim),
+        (DisplayStyle::Both.name(), DisplayStyle::Both),
+    ];
+}
+
+/// A line printed by the `record` command, see [`Command::Import`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Import {
+    /// `seed: <seed>`, `-` standing for an unknown seed. Starts importing a new recording.
+    Seed(Option<u32>),
+    /// `ticks: <ticks>`
+    Ticks(u32),
+    /// `entries: <first>..<end>`, the range of entries following.
+    Entries { first: usize, end: usize },
+    /// `<tick> rotate` or `<tick> column <column>`
+    Entry(Entry),
+}
+
+impl Import {
+    #[allow(clippy::cast_possible_truncation)]
+    const LAST_COLUMN: u8 = Board::COLUMNS as u8 - 1;
+
+    fn parse(args: &mut Arguments<'_>) -> Result<Self, CommandError> {
+        let token = args.token().ok_or(CommandError::MissingArgument)?;
+        let import = match token {
+            "seed:" => match args.token().ok_or(CommandError::MissingArgument)? {
+                "-" => Self::Seed(None),
+                seed => Self::Seed(Some(Argument::parse(seed)?)),
+            },
+            "ticks:" => Self::Ticks(args.required()?),
+            "entries:" => {
+                let range: &str = args.required()?;
+                let (first, end) = range
+                    .split_once("..")
+                    .and_then(|(first, end)| Some((first.parse().ok()?, end.parse().ok()?)))
+                    .filter(|(first, end)| first <= end)
+                    .ok_or_else(|| CommandError::InvalidNumber(Token::from(range)))?;
+                Self::Entries { first, end }
+            }
+            tick => {
+                let tick = Argument::parse(tick)?;
+                let step = match args.token().ok_or(CommandError::MissingArgument)? {
+                    "rotate" => Step::Rotate,
+                    "column" => Step::MoveTo(args.required_within(0..=Self::LAST_COLUMN)?),
+                    step => return Err(CommandError::InvalidKeyword(Token::from(step))),
+                };
+                Self::Entry(Entry { tick, step })
+            }
+        };
+        Ok(import)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Version,
+    Help,
+    Pause,
+    Resume,
+    Score,
+    Seed,
+    Next,
+    /// Prints the recorded input, limited to the given number of most recent entries.
+    Record(Option<usize>),
+    /// Imports a line of the recorded input, as printed by [`Record`](Self::Record).
+    Import(Import),
+    Replay,
+    Calibrate,
+    Board,
+    /// Turns the live view of the board on or off, or toggles it if not specified.
+    Mirror(Option<Switch>),
+    /// Steers the game from the keyboard, see [`Key`](super::keys::Key).
+    Play,
+    /// Abandons the current game and starts a new one.
+    Reset,
+    /// Changes the game's [`Speed`], keeping what is not specified. Prints the current speed
+    /// if neither is specified.
+    Speed {
+        period_ms: Option<u16>,
+        soft_drop: Option<u8>,
+    },
+    /// Prints the given setting, or all of them if not specified.
+    Get(Option<Key>),
+    /// Changes a setting, checked against its [`range`](Key::range).
+    Set(Key, u32),
+    /// Persists the current settings.
+    Save,
+    /// Reverts the settings to their defaults, keeping the calibration.
+    Defaults,
+    /// Lists the high scores unless told otherwise.
+    Scores(Scores),
+    /// Changes how the falling tile stands out, or prints it if not specified.
+    Display(Option<DisplayStyle>),
+}
+
+impl Command {
+    fn parse(args: &mut Arguments<'_>) -> Result<Self, CommandError> {
+        let Some(name) = args.token() else {
+            return Err(CommandError::UnknownCommand(Token::from("")));
+        };
+        let cmd = match name {
+            "ver" => Self::Version,
+            "help" => Self::Help,
+            "pause" => Self::Pause,
+            "resume" => Self::Resume,
+            "score" => Self::Score,
+            "seed" => Self::Seed,
+            "next" => Self::Next,
+            "record" => Self::Record(args.optional()?),
+            "import" => Self::Import(Import::parse(args)?),
+            "replay" => Self::Replay,
+            "calibrate" => Self::Calibrate,
+            "board" => Self::Board,
+            "mirror" => Self::Mirror(args.optional()?),
+            "play" => Self::Play,
+            "reset" => Self::Reset,
+            "speed" => Self::Speed {
+                period_ms: args.optional_within(Speed::PERIODS)?,
+                soft_drop: args.optional_within(Speed::SOFT_DROPS)?,
+            },
+            "get" => Self::Get(args.optional()?),
+            "set" => {
+                let key: Key = args.required()?;
+                Self::Set(key, args.required_within(key.range())?)
+            }
+            "save" => Self::Save,
+            "defaults" => Self::Defaults,
+            "scores" => Self::Scores(args.optional()?.unwrap_or(Scores::List)),
+            "display" => Self::Display(args.optional()?),
+            _ => return Err(CommandError::UnknownCommand(Token::from(name))),
+        };
+        Ok(cmd)
+    }
+}
+
+impl TryFrom<&[u8]> for Command {
+    type Error = CommandError;
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        let line = str::from_utf8(value).map_err(|_| CommandError::InvalidEncoding)?;
+        let mut args = Arguments::new(line);
+        let cmd = Self::parse(&mut args)?;
+        args.finish()?;
+        Ok(cmd)
+    }
+}
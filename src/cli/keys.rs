@@ -0,0 +1,98 @@
+//! Playing the game from the terminal's keyboard.
+
+use crate::game::message::Message;
+
+/// A key as sent by a VT100 compatible terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Left,
+    Right,
+    Up,
+    Down,
+    Char(u8),
+}
+
+impl Key {
+    /// Ends keyboard play.
+    pub const QUIT: Self = Self::Char(b'q');
+    /// Ctrl-C, ending keyboard play as well.
+    pub const INTERRUPT: Self = Self::Char(0x03);
+
+    /// The game [`Message`] the key stands for, if any.
+    ///
+    /// The arrow keys move and rotate (up) the tile or drop it a row (down), space rotates as well
+    /// and `p` toggles the pause.
+    #[must_use]
+    pub fn message(self) -> Option<Message> {
+        match self {
+            Key::Left => Some(Message::MoveLeft),
+            Key::Right => Some(Message::MoveRight),
+            Key::Up | Key::Char(b' ') => Some(Message::BtnBPress),
+            Key::Down => Some(Message::TimerTick),
+            Key::Char(b'p') => Some(Message::TogglePause),
+            Key::Char(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Idle,
+    Escape,
+    /// Within a control sequence (`ESC [`) or single shift (`ESC O`), the latter being sent by
+    /// terminals in application cursor mode.
+    Sequence,
+}
+
+/// Decodes the bytes received from a terminal into [`Key`]s, swallowing any escape sequences
+/// other than the arrow keys.
+#[derive(Debug)]
+pub struct KeyDecoder {
+    state: State,
+}
+
+impl KeyDecoder {
+    const ESCAPE: u8 = 0x1b;
+
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { state: State::Idle }
+    }
+
+    /// Accounts for the received `byte`, returning the key once complete.
+    pub fn feed(&mut self, byte: u8) -> Option<Key> {
+        match self.state {
+            State::Idle if byte == Self::ESCAPE => {
+                self.state = State::Escape;
+                None
+            }
+            State::Idle => Some(Key::Char(byte)),
+            State::Escape => {
+                self.state = if byte == b'[' || byte == b'O' {
+                    State::Sequence
+                } else {
+                    State::Idle
+                };
+                None
+            }
+            // parameter and intermediate bytes are followed by a single final byte
+            State::Sequence if (0x20..0x40).contains(&byte) => None,
+            State::Sequence => {
+                self.state = State::Idle;
+                match byte {
+                    b'A' => Some(Key::Up),
+                    b'B' => Some(Key::Down),
+                    b'C' => Some(Key::Right),
+                    b'D' => Some(Key::Left),
+                    _ => None,
+                }
+            }
+        }
+    }
+}
+
+impl Default for KeyDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
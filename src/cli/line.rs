@@ -0,0 +1,141 @@
+//! Line editing for stock serial terminals.
+
+use core::mem;
+use heapless::Vec;
+
+/// Printed whenever the CLI is ready for the next command.
+pub const PROMPT: &str = "$ ";
+
+/// Echo confirming a finished line.
+pub const NEWLINE: &str = "\r\n";
+
+/// Echo erasing the last character on the terminal.
+pub const ERASE: &str = "\x08 \x08";
+
+const BACKSPACE: u8 = 0x08;
+const DELETE: u8 = 0x7f;
+const ESCAPE: u8 = 0x1b;
+
+/// What the terminal should be told in response to a received byte.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Edit<'a> {
+    /// Nothing to do, e.g. the byte is not printable.
+    None,
+    /// The byte has been appended to the line and should be echoed.
+    Echo(u8),
+    /// The last character has been removed from the line, see [`ERASE`].
+    Erase,
+    /// The line is complete, see [`NEWLINE`].
+    Submit(&'a [u8]),
+    /// The line has grown too long and has been discarded. The remaining input is ignored up to
+    /// the next terminator.
+    Overflow,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Editing,
+    /// Swallowing an ANSI escape sequence (e.g. sent by the arrow keys).
+    Escape,
+    /// Swallowing the parameters of an ANSI control sequence.
+    ControlSequence,
+    /// Waiting for the end of an overlong line.
+    Discarding,
+}
+
+/// Assembles lines from single bytes as typed into a serial terminal.
+///
+/// Lines end with `\r`, `\n` or `;`, so `\r\n` line endings, several `;`-separated commands per
+/// line and the original `<cmd>;` syntax all work. Backspace and DEL remove the last character.
+#[derive(Debug)]
+pub struct LineEditor<const N: usize> {
+    line: Vec<u8, N>,
+    mode: Mode,
+    /// Whether the previous byte was a `\r`, so that a following `\n` does not submit an empty
+    /// line.
+    after_cr: bool,
+    /// Whether `line` has been submitted. It is handed out by reference, so it is only cleared on
+    /// the next byte.
+    submitted: bool,
+}
+
+impl<const N: usize> LineEditor<N> {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            line: Vec::new(),
+            mode: Mode::Editing,
+            after_cr: false,
+            submitted: false,
+        }
+    }
+
+    fn is_terminator(byte: u8) -> bool {
+        matches!(byte, b'\r' | b'\n' | b';')
+    }
+
+    /// Accounts for the received `byte`.
+    pub fn feed(&mut self, byte: u8) -> Edit<'_> {
+        let after_cr = mem::replace(&mut self.after_cr, byte == b'\r');
+        if mem::take(&mut self.submitted) {
+            self.line.clear();
+        }
+
+        match self.mode {
+            Mode::Escape => {
+                self.mode = if byte == b'[' {
+                    Mode::ControlSequence
+                } else {
+                    Mode::Editing
+                };
+                return Edit::None;
+            }
+            Mode::ControlSequence => {
+                // parameter and intermediate bytes are followed by a single final byte
+                if !(0x20..0x40).contains(&byte) {
+                    self.mode = Mode::Editing;
+                }
+                return Edit::None;
+            }
+            Mode::Discarding => {
+                if Self::is_terminator(byte) {
+                    self.mode = Mode::Editing;
+                }
+                return Edit::None;
+            }
+            Mode::Editing => {}
+        }
+
+        match byte {
+            b'\n' if after_cr => Edit::None,
+            _ if Self::is_terminator(byte) => {
+                self.submitted = true;
+                Edit::Submit(&self.line)
+            }
+            BACKSPACE | DELETE => match self.line.pop() {
+                Some(_) => Edit::Erase,
+                None => Edit::None,
+            },
+            ESCAPE => {
+                self.mode = Mode::Escape;
+                Edit::None
+            }
+            b' '..=b'~' => {
+                if self.line.push(byte).is_ok() {
+                    Edit::Echo(byte)
+                } else {
+                    self.line.clear();
+                    self.mode = Mode::Discarding;
+                    Edit::Overflow
+                }
+            }
+            _ => Edit::None,
+        }
+    }
+}
+
+impl<const N: usize> Default for LineEditor<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
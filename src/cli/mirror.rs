@@ -0,0 +1,96 @@
+//! Live view of the game for VT100 compatible terminals.
+//!
+//! The board is drawn in the top rows of the terminal, while the rest of the terminal keeps
+//! scrolling as usual, so the CLI stays usable below the board.
+
+use crate::game::{
+    board::Board,
+    status::{Phase, Status},
+};
+use core::fmt::{Result as FmtResult, Write};
+
+/// Number of terminal rows taken by [`render`].
+pub const HEIGHT: usize = Board::ROWS + 3;
+
+/// Upper bound of the bytes written by [`render`].
+pub const FRAME_CAPACITY: usize = 512;
+
+/// Clears the terminal and restricts scrolling to the rows below the board (leaving a blank row),
+/// moving the cursor there.
+pub const ENTER: &str = "\x1b[2J\x1b[10r\x1b[10;1H";
+
+/// Undoes [`ENTER`].
+pub const LEAVE: &str = "\x1b[r\x1b[2J\x1b[H";
+
+const SAVE_CURSOR: &str = "\x1b7";
+const RESTORE_CURSOR: &str = "\x1b8";
+const HOME: &str = "\x1b[H";
+const CLEAR_LINE: &str = "\x1b[K";
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Empty,
+    Falling,
+    Settled,
+}
+
+impl Cell {
+    fn background(self) -> &'static str {
+        match self {
+            Cell::Empty => "\x1b[49m",
+            Cell::Falling => "\x1b[43m",
+            Cell::Settled => "\x1b[46m",
+        }
+    }
+}
+
+/// Draws `status` in the top rows of the terminal, leaving the cursor where it was.
+///
+/// Each cell is two characters wide, the falling tile is yellow, the settled cells are cyan.
+/// Colour changes are only emitted where needed to keep the frame small.
+pub fn render<W>(out: &mut W, status: &Status) -> FmtResult
+where
+    W: Write,
+{
+    write!(
+        out,
+        "{SAVE_CURSOR}{HOME}{BOLD}microtile{RESET}  score {}  level {}  {}",
+        status.score.points,
+        status.score.level,
+        status.phase.name()
+    )?;
+    if let Phase::TileFloating { column } = status.phase {
+        write!(out, " (column {column})")?;
+    }
+    if status.paused {
+        write!(out, ", paused")?;
+    }
+    write!(out, "{CLEAR_LINE}\r\n+----------+{CLEAR_LINE}\r\n")?;
+
+    // Row 0 is the bottom row, so print from top to bottom
+    for row in (0..Board::ROWS).rev() {
+        write!(out, "|")?;
+        let mut current = Cell::Empty;
+        for column in 0..Board::COLUMNS {
+            let cell = if status.active.is_set(row, column) {
+                Cell::Falling
+            } else if status.passive.is_set(row, column) {
+                Cell::Settled
+            } else {
+                Cell::Empty
+            };
+            if cell != current {
+                out.write_str(cell.background())?;
+                current = cell;
+            }
+            out.write_str("  ")?;
+        }
+        if current != Cell::Empty {
+            out.write_str(RESET)?;
+        }
+        write!(out, "|{CLEAR_LINE}\r\n")?;
+    }
+    write!(out, "+----------+{CLEAR_LINE}{RESTORE_CURSOR}")
+}
@@ -0,0 +1,9 @@
+//! Hardware-independent parts of the command line interface.
+//!
+//! The UART drivers wiring these up live in `device::cli`.
+
+pub mod command;
+pub mod keys;
+pub mod line;
+pub mod mirror;
+pub mod parse;
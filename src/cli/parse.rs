@@ -0,0 +1,153 @@
+//! Tokenizing and parsing of command arguments.
+
+use super::command::{CommandError, Token};
+use core::{ops::RangeInclusive, str::SplitAsciiWhitespace};
+
+/// The whitespace-separated tokens of a command line following the command itself.
+#[derive(Debug, Clone)]
+pub struct Arguments<'a> {
+    tokens: SplitAsciiWhitespace<'a>,
+}
+
+impl<'a> Arguments<'a> {
+    #[must_use]
+    pub fn new(line: &'a str) -> Self {
+        Self {
+            tokens: line.split_ascii_whitespace(),
+        }
+    }
+
+    /// The next token without interpreting it, e.g. the command's name.
+    pub fn token(&mut self) -> Option<&'a str> {
+        self.tokens.next()
+    }
+
+    /// Parses the next token, failing with [`CommandError::MissingArgument`] if there is none.
+    pub fn required<T>(&mut self) -> Result<T, CommandError>
+    where
+        T: Argument<'a>,
+    {
+        self.optional()?.ok_or(CommandError::MissingArgument)
+    }
+
+    /// Parses the next number, failing with [`CommandError::InvalidNumber`] if it is not within
+    /// `range`.
+    pub fn required_within<T>(&mut self, range: RangeInclusive<T>) -> Result<T, CommandError>
+    where
+        T: Argument<'a> + PartialOrd,
+    {
+        self.optional_within(range)?
+            .ok_or(CommandError::MissingArgument)
+    }
+
+    /// Parses the next number, if any, see [`required_within`](Self::required_within).
+    pub fn optional_within<T>(
+        &mut self,
+        range: RangeInclusive<T>,
+    ) -> Result<Option<T>, CommandError>
+    where
+        T: Argument<'a> + PartialOrd,
+    {
+        let Some(token) = self.tokens.next() else {
+            return Ok(None);
+        };
+        let value = T::parse(token)?;
+        if range.contains(&value) {
+            Ok(Some(value))
+        } else {
+            Err(CommandError::InvalidNumber(Token::from(token)))
+        }
+    }
+
+    /// Parses the next token, if any.
+    pub fn optional<T>(&mut self) -> Result<Option<T>, CommandError>
+    where
+        T: Argument<'a>,
+    {
+        self.tokens.next().map(T::parse).transpose()
+    }
+
+    /// Makes sure all tokens have been consumed, failing with
+    /// [`CommandError::UnexpectedArgument`] otherwise.
+    pub fn finish(mut self) -> Result<(), CommandError> {
+        match self.tokens.next() {
+            Some(token) => Err(CommandError::UnexpectedArgument(Token::from(token))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A value that can be parsed from a single token.
+pub trait Argument<'a>: Sized {
+    fn parse(token: &'a str) -> Result<Self, CommandError>;
+}
+
+impl<'a> Argument<'a> for &'a str {
+    fn parse(token: &'a str) -> Result<Self, CommandError> {
+        Ok(token)
+    }
+}
+
+macro_rules! impl_argument_for_int {
+    ($($int:ty),*) => {
+        $(
+            impl Argument<'_> for $int {
+                fn parse(token: &str) -> Result<Self, CommandError> {
+                    token
+                        .parse()
+                        .map_err(|_| CommandError::InvalidNumber(Token::from(token)))
+                }
+            }
+        )*
+    };
+}
+
+impl_argument_for_int!(u8, u16, u32, usize, i8, i16, i32);
+
+/// An argument taking one of a fixed set of values, each one spelled as a keyword.
+pub trait Keyword: Copy + PartialEq + 'static {
+    /// The accepted keywords and the values they stand for.
+    const KEYWORDS: &'static [(&'static str, Self)];
+
+    /// The keyword spelling `self`.
+    fn keyword(self) -> &'static str {
+        Self::KEYWORDS
+            .iter()
+            .find_map(|(keyword, value)| (*value == self).then_some(*keyword))
+            .unwrap_or("?")
+    }
+}
+
+impl<T> Argument<'_> for T
+where
+    T: Keyword,
+{
+    fn parse(token: &str) -> Result<Self, CommandError> {
+        T::KEYWORDS
+            .iter()
+            .find_map(|(keyword, value)| (*keyword == token).then_some(*value))
+            .ok_or_else(|| CommandError::InvalidKeyword(Token::from(token)))
+    }
+}
+
+/// A `key=value` argument, parsing the value as `V`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Assignment<'a, V> {
+    pub key: &'a str,
+    pub value: V,
+}
+
+impl<'a, V> Argument<'a> for Assignment<'a, V>
+where
+    V: Argument<'a>,
+{
+    fn parse(token: &'a str) -> Result<Self, CommandError> {
+        match token.split_once('=') {
+            Some((key, value)) if !key.is_empty() => Ok(Self {
+                key,
+                value: V::parse(value)?,
+            }),
+            _ => Err(CommandError::InvalidAssignment(Token::from(token))),
+        }
+    }
+}
@@ -1,10 +1,11 @@
 use crate::game::{driver::MAILBOX_CAPACITY, message::Message};
 use core::marker::PhantomData;
 use microbit::{
-    gpio::BTN_B,
+    gpio::{BTN_A, BTN_B},
     hal::{
         gpio::{Floating, Input, Pin},
         gpiote::{GpioteChannel, GpioteChannelEvent},
+        prelude::InputPin,
     },
 };
 use rtic_sync::channel::{Sender, TrySendError};
@@ -35,6 +36,8 @@ impl<'b> GpioResources<'b> {
 pub struct RotationDriver<'a, 'b, S> {
     gpio: &'b GpioResources<'b>,
     button_event: GpioteChannelEvent<'b, Pin<Input<Floating>>>,
+    // pressing button B while holding down this button toggles the pause instead of rotating
+    modifier: &'b BTN_A,
     command_pipe: Sender<'a, Message, MAILBOX_CAPACITY>,
     s: PhantomData<S>,
 }
@@ -43,6 +46,7 @@ impl<'a, 'b> RotationDriver<'a, 'b, Stopped> {
     #[must_use]
     pub fn new(
         resources: &'b GpioResources<'b>,
+        modifier: &'b BTN_A,
         mailbox: Sender<'a, Message, MAILBOX_CAPACITY>,
     ) -> Self {
         resources.channel.reset_events();
@@ -54,6 +58,7 @@ impl<'a, 'b> RotationDriver<'a, 'b, Stopped> {
         Self {
             gpio: resources,
             button_event: event,
+            modifier,
             command_pipe: mailbox,
             s: PhantomData,
         }
@@ -65,6 +70,7 @@ impl<'a, 'b> RotationDriver<'a, 'b, Stopped> {
         RotationDriver {
             gpio: self.gpio,
             button_event: self.button_event,
+            modifier: self.modifier,
             command_pipe: self.command_pipe,
             s: PhantomData,
         }
@@ -79,6 +85,7 @@ impl<'a, 'b> RotationDriver<'a, 'b, Started> {
         RotationDriver {
             gpio: self.gpio,
             button_event: self.button_event,
+            modifier: self.modifier,
             command_pipe: self.command_pipe,
             s: PhantomData,
         }
@@ -92,7 +99,17 @@ impl<'a, 'b> RotationDriver<'a, 'b, Started> {
         // https://infocenter.nordicsemi.com/topic/ps_nrf52833/gpiote.html?cp=5_1_0_5_8
         if self.gpio.channel.is_event_triggered() {
             self.gpio.channel.reset_events();
-            self.command_pipe.try_send(Message::BtnBPress)
+
+            // button is active low
+            if self
+                .modifier
+                .is_low()
+                .expect("getting input pin state should always be valid")
+            {
+                self.command_pipe.try_send(Message::TogglePause)
+            } else {
+                self.command_pipe.try_send(Message::BtnBPress)
+            }
         } else {
             // event does not belong to our channel -> ignore it
             Ok(())
@@ -1,29 +1,59 @@
-use crate::util::nb_async;
+use crate::{
+    cli::{
+        command::{Command, CommandError},
+        keys::{Key, KeyDecoder},
+        line::{Edit, LineEditor, ERASE, NEWLINE, PROMPT},
+    },
+    game::{driver::MAILBOX_CAPACITY as GAME_CAPACITY, message::Message as GameMessage},
+};
 
-use super::command::Command;
-use heapless::Vec;
+use super::interrupt::{wait_for, UarteEvent, Wakers};
+use super::uplink::{Uplink, UplinkClosed};
 use microbit::hal::{
     prelude::_embedded_hal_serial_Read,
     uarte::{Instance, UarteRx},
 };
-use rtic_sync::channel::Sender;
+use rtic_sync::channel::{Sender, TrySendError};
 
 pub const MAILBOX_CAPACITY: usize = 16;
 
-const IN_BUFFER_SIZE: usize = 8;
+/// Maximum length of a line.
+pub const IN_BUFFER_SIZE: usize = 64;
+
+/// A line received from the user.
+#[derive(Debug)]
+pub enum Input {
+    Command(Command),
+    /// The line is not a valid command.
+    Invalid(CommandError),
+    /// The line exceeded [`IN_BUFFER_SIZE`] and has been discarded.
+    Overflow,
+}
 
 #[derive(Debug)]
 pub enum DriverError {
     ReceiverDropped,
+    UplinkReceiverDropped,
+    GameReceiverDropped,
 }
 
+/// Reads commands from the UART, echoing the input so that a stock serial terminal can be used
+/// (see [`LineEditor`]).
+///
+/// The `play` command switches to reading keys instead, which are translated into game messages
+/// (see [`Key`]) until the user quits.
 pub struct DownlinkDriver<T>
 where
     T: Instance,
 {
     rx: UarteRx<T>,
-    buffer_in: Vec<u8, IN_BUFFER_SIZE>,
-    command_pipe: Sender<'static, Command, MAILBOX_CAPACITY>,
+    editor: LineEditor<IN_BUFFER_SIZE>,
+    command_pipe: Sender<'static, Input, MAILBOX_CAPACITY>,
+    outgoing: Uplink,
+    game: Sender<'static, GameMessage, GAME_CAPACITY>,
+    // While playing, the input is decoded into keys rather than edited as lines.
+    keys: Option<KeyDecoder>,
+    wakers: &'static Wakers,
 }
 
 impl<T> DownlinkDriver<T>
@@ -31,41 +61,136 @@ where
     T: Instance,
 {
     #[must_use]
-    pub fn new(rx: UarteRx<T>, mailbox: Sender<'static, Command, MAILBOX_CAPACITY>) -> Self {
+    pub fn new(
+        rx: UarteRx<T>,
+        mailbox: Sender<'static, Input, MAILBOX_CAPACITY>,
+        outgoing: Uplink,
+        game: Sender<'static, GameMessage, GAME_CAPACITY>,
+        wakers: &'static Wakers,
+    ) -> Self {
         Self {
             rx,
-            buffer_in: Vec::<u8, IN_BUFFER_SIZE>::new(),
+            editor: LineEditor::new(),
             command_pipe: mailbox,
+            outgoing,
+            game,
+            keys: None,
+            wakers,
         }
     }
 
+    async fn forward(&mut self, input: Input) -> Result<(), DriverError> {
+        self.command_pipe
+            .send(input)
+            .await
+            .map_err(|_| DriverError::ReceiverDropped)
+    }
+
     pub async fn run(&mut self) -> Result<(), DriverError> {
+        echo(&mut self.outgoing, PROMPT).await?;
+
         loop {
-            if let Ok(byte) = nb_async(|| self.rx.read()).await {
+            let byte = wait_for::<T, _, _, _>(UarteEvent::EndRx, self.wakers, || self.rx.read());
+            if let Ok(byte) = byte.await {
                 defmt::trace!("Received byte, processing it now.");
 
-                if byte == b';' {
-                    if let Ok(cmd) = Command::try_from(self.buffer_in.as_slice()) {
-                        self.command_pipe
-                            .send(cmd)
-                            .await
-                            .map_err(|_| DriverError::ReceiverDropped)?;
-                    }
-
-                    defmt::info!("End of command detected, clearing the input buffer now.");
-                    self.buffer_in.clear();
+                if self.keys.is_some() {
+                    self.play(byte).await?;
                 } else {
-                    if self.buffer_in.is_full() {
-                        defmt::warn!(
-                            "Input buffer reached total capacity. \
-                            Clearing the input buffer now, please start over again."
-                        );
-                        self.buffer_in.clear();
-                    }
-                    // Safety: we've just made sure the buffer is not full
-                    unsafe { self.buffer_in.push_unchecked(byte) };
+                    self.edit(byte).await?;
                 }
             };
         }
     }
+
+    async fn edit(&mut self, byte: u8) -> Result<(), DriverError> {
+        match self.editor.feed(byte) {
+            Edit::None => Ok(()),
+            Edit::Echo(byte) => {
+                let mut buffer = [0; 4];
+                echo(
+                    &mut self.outgoing,
+                    char::from(byte).encode_utf8(&mut buffer),
+                )
+                .await
+            }
+            Edit::Erase => echo(&mut self.outgoing, ERASE).await,
+            Edit::Submit(line) => {
+                defmt::info!("End of command detected.");
+                let input = (!line.iter().all(u8::is_ascii_whitespace))
+                    .then(|| Command::try_from(line).map_or_else(Input::Invalid, Input::Command));
+                echo(&mut self.outgoing, NEWLINE).await?;
+
+                match input {
+                    // Switching to reading keys is up to the downlink itself
+                    Some(Input::Command(Command::Play)) => self.start_playing().await,
+                    // the receiver prompts for the next command once done
+                    Some(input) => self.forward(input).await,
+                    None => echo(&mut self.outgoing, PROMPT).await,
+                }
+            }
+            Edit::Overflow => {
+                defmt::warn!("Input buffer reached total capacity. Discarding the current line.");
+                self.forward(Input::Overflow).await
+            }
+        }
+    }
+
+    async fn start_playing(&mut self) -> Result<(), DriverError> {
+        defmt::info!("Reading keys to play the game.");
+        self.keys = Some(KeyDecoder::new());
+        echo(
+            &mut self.outgoing,
+            "\r\n\
+            Playing from the keyboard:\r\n\
+            - left/right arrow - moves the tile\r\n\
+            - up arrow/space - rotates the tile, or starts a new game once over\r\n\
+            - down arrow - drops the tile by a row\r\n\
+            - p - pauses and resumes the game\r\n\
+            - q - stops playing from the keyboard\r\n",
+        )
+        .await
+    }
+
+    async fn play(&mut self, byte: u8) -> Result<(), DriverError> {
+        let Some(key) = self.keys.as_mut().and_then(|keys| keys.feed(byte)) else {
+            return Ok(());
+        };
+
+        if key == Key::QUIT || key == Key::INTERRUPT {
+            defmt::info!("Back to reading commands.");
+            self.keys = None;
+            echo(
+                &mut self.outgoing,
+                "\r\nStopped playing from the keyboard.\r\n",
+            )
+            .await?;
+            return echo(&mut self.outgoing, PROMPT).await;
+        }
+
+        let Some(msg) = key.message() else {
+            return Ok(());
+        };
+        match self.game.try_send(msg) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(_)) => {
+                defmt::warn!("Dropping a key to allow the engine to catch up.");
+                Ok(())
+            }
+            Err(TrySendError::NoReceiver(_)) => Err(DriverError::GameReceiverDropped),
+        }
+    }
+}
+
+impl From<UplinkClosed> for DriverError {
+    fn from(_: UplinkClosed) -> Self {
+        Self::UplinkReceiverDropped
+    }
+}
+
+/// Sends `text` to the terminal.
+///
+/// Note: this is not a method, so that it may be called while the editor's line is borrowed.
+async fn echo(outgoing: &mut Uplink, text: &str) -> Result<(), DriverError> {
+    Ok(outgoing.write_str(text).await?)
 }
@@ -0,0 +1,144 @@
+//! Waiting for the UARTE's transfers without busy polling.
+//!
+//! The drivers keep using the HAL's non-blocking `read`/`flush`. Whenever those would block, the
+//! waiting task registers its waker and enables the interrupt of the UARTE event it waits for. The
+//! interrupt in turn disables itself and wakes the task, which then retries. The events themselves
+//! are left for the HAL to consume.
+
+use core::{
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures::task::AtomicWaker;
+use microbit::hal::uarte::Instance;
+use nb::{Error, Result as NbResult};
+
+/// The UARTE events a driver may wait for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UarteEvent {
+    /// The receive buffer has been filled.
+    EndRx,
+    /// The transmit buffer has been sent.
+    EndTx,
+}
+
+impl UarteEvent {
+    fn enable<T>(self)
+    where
+        T: Instance,
+    {
+        // SAFETY: INTENSET is write-one-to-set, so this does not interfere with the HAL's or the
+        // interrupt's accesses.
+        let uarte = unsafe { &*T::ptr() };
+        match self {
+            UarteEvent::EndRx => uarte.intenset.write(|w| w.endrx().set()),
+            UarteEvent::EndTx => uarte.intenset.write(|w| w.endtx().set()),
+        }
+    }
+}
+
+/// The tasks waiting for a UARTE event, see [`UarteInterrupt`].
+#[derive(Debug, Default)]
+pub struct Wakers {
+    rx: AtomicWaker,
+    tx: AtomicWaker,
+}
+
+impl Wakers {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            rx: AtomicWaker::new(),
+            tx: AtomicWaker::new(),
+        }
+    }
+
+    fn get(&self, event: UarteEvent) -> &AtomicWaker {
+        match event {
+            UarteEvent::EndRx => &self.rx,
+            UarteEvent::EndTx => &self.tx,
+        }
+    }
+}
+
+/// Future retrying a non-blocking UARTE operation each time `event` occurs.
+pub struct Wait<'a, T, F> {
+    f: F,
+    event: UarteEvent,
+    wakers: &'a Wakers,
+    uarte: PhantomData<T>,
+}
+
+impl<T, F, R, E> Future for Wait<'_, T, F>
+where
+    T: Instance,
+    F: FnMut() -> NbResult<R, E> + Unpin,
+{
+    type Output = Result<R, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        // Registering before trying makes sure an event occurring in between is not missed.
+        this.wakers.get(this.event).register(cx.waker());
+        match (this.f)() {
+            Ok(val) => Poll::Ready(Ok(val)),
+            Err(Error::Other(e)) => Poll::Ready(Err(e)),
+            Err(Error::WouldBlock) => {
+                // If the event has occurred meanwhile, the interrupt fires right away.
+                this.event.enable::<T>();
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Retries `f` each time `event` occurs on the UARTE `T`, until it no longer blocks.
+pub fn wait_for<T, F, R, E>(event: UarteEvent, wakers: &Wakers, f: F) -> Wait<'_, T, F>
+where
+    T: Instance,
+    F: FnMut() -> NbResult<R, E> + Unpin,
+{
+    Wait {
+        f,
+        event,
+        wakers,
+        uarte: PhantomData,
+    }
+}
+
+/// Handles the interrupt of the UARTE `T`, waking whoever waits for an event that has occurred.
+pub struct UarteInterrupt<T> {
+    wakers: &'static Wakers,
+    uarte: PhantomData<T>,
+}
+
+impl<T> UarteInterrupt<T>
+where
+    T: Instance,
+{
+    #[must_use]
+    pub fn new(wakers: &'static Wakers) -> Self {
+        Self {
+            wakers,
+            uarte: PhantomData,
+        }
+    }
+
+    pub fn handle_uarte_event(&mut self) {
+        // SAFETY: only the write-one-to-clear INTENCLR is written, see `UarteEvent::enable`.
+        let uarte = unsafe { &*T::ptr() };
+
+        // The events stay set until the HAL consumes them, so their interrupts have to be disabled
+        // until waited for again.
+        if uarte.events_endrx.read().bits() != 0 {
+            uarte.intenclr.write(|w| w.endrx().clear());
+            self.wakers.rx.wake();
+        }
+        if uarte.events_endtx.read().bits() != 0 {
+            uarte.intenclr.write(|w| w.endtx().clear());
+            self.wakers.tx.wake();
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use super::uplink::{Uplink, MAILBOX_CAPACITY, MESSAGE_LENGTH};
+use crate::{
+    cli::mirror::{render, FRAME_CAPACITY},
+    game::status::SharedStatus,
+};
+use core::sync::atomic::{AtomicBool, Ordering};
+
+// A whole frame has to fit into the empty mailbox.
+const _: () = assert!(FRAME_CAPACITY <= MAILBOX_CAPACITY * MESSAGE_LENGTH);
+
+/// Streams the board to the terminal while enabled, see [`crate::cli::mirror`].
+///
+/// Unlike the CLI's replies, the frames never wait for the uplink. A frame is only drawn if the
+/// uplink is idle and dropped otherwise, so that neither the game nor the CLI get held up. Each
+/// frame redraws the whole board, so the next one makes up for any dropped ones.
+pub struct Mirror {
+    outgoing: Uplink,
+    enabled: &'static AtomicBool,
+    status: &'static SharedStatus,
+}
+
+impl Mirror {
+    #[must_use]
+    pub fn new(
+        outgoing: Uplink,
+        enabled: &'static AtomicBool,
+        status: &'static SharedStatus,
+    ) -> Self {
+        Self {
+            outgoing,
+            enabled,
+            status,
+        }
+    }
+
+    /// Redraws the board, if enabled.
+    pub fn refresh(&mut self) {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return;
+        }
+        // A whole frame fits into the empty mailbox, so it is never cut short.
+        if !self.outgoing.is_idle() {
+            defmt::debug!("Dropping mirrored frame, the uplink is busy.");
+            return;
+        }
+
+        let status = self.status.get();
+        match self.outgoing.try_write_with(|out| render(out, &status)) {
+            Ok(true) => {}
+            Ok(false) => defmt::warn!("Mirrored frame got cut short."),
+            Err(_) => defmt::warn!("Dropping mirrored frame, the uplink is gone."),
+        }
+    }
+}
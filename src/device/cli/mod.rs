@@ -1,15 +1,30 @@
-use self::command::Command;
-use crate::device::cli::{
-    downlink::{DownlinkDriver, MAILBOX_CAPACITY as DOWNLINK_CAPACITY},
-    receiver::CommandReceiver,
-    uplink::UplinkDriver,
+use crate::{
+    device::{
+        cli::{
+            downlink::{DownlinkDriver, Input, MAILBOX_CAPACITY as DOWNLINK_CAPACITY},
+            interrupt::{UarteInterrupt, Wakers},
+            mirror::Mirror,
+            notifier::Notifier,
+            receiver::CommandReceiver,
+            uplink::UplinkDriver,
+        },
+        storage::Storage,
+    },
+    game::{
+        driver::MAILBOX_CAPACITY as GAME_CAPACITY, highscore::SharedHighScores,
+        message::Message as GameMessage, record::SharedRecording, status::SharedStatus,
+    },
+    settings::SharedSettings,
 };
+use core::sync::atomic::AtomicBool;
 use microbit::hal::uarte::{Baudrate, Error, Instance, Parity, Pins, Uarte};
-use rtic_sync::channel::Channel;
-use uplink::{Message, MAILBOX_CAPACITY as UPLINK_CAPACITY};
+use rtic_sync::channel::{Channel, Sender};
+use uplink::{Message, Uplink, MAILBOX_CAPACITY as UPLINK_CAPACITY};
 
-pub mod command;
 pub mod downlink;
+pub mod interrupt;
+pub mod mirror;
+pub mod notifier;
 pub mod receiver;
 pub mod uplink;
 
@@ -17,7 +32,8 @@ pub struct Resources {
     peripheral_tx_buf: [u8; 255],
     peripheral_rx_buf: [u8; 1],
     str_channel: Channel<Message, UPLINK_CAPACITY>,
-    cmd_channel: Channel<Command, DOWNLINK_CAPACITY>,
+    cmd_channel: Channel<Input, DOWNLINK_CAPACITY>,
+    wakers: Wakers,
 }
 
 impl Default for Resources {
@@ -27,25 +43,62 @@ impl Default for Resources {
             peripheral_rx_buf: [0; 1],
             str_channel: Channel::new(),
             cmd_channel: Channel::new(),
+            wakers: Wakers::new(),
         }
     }
 }
 
+/// State the CLI shares with the rest of the application.
+#[derive(Clone, Copy)]
+pub struct Shared {
+    pub status: &'static SharedStatus,
+    pub recording: &'static SharedRecording,
+    pub settings: &'static SharedSettings,
+    pub high_scores: &'static SharedHighScores,
+    pub storage: &'static Storage,
+    /// Whether the board is mirrored to the terminal, see [`Mirror`].
+    pub mirror_enabled: &'static AtomicBool,
+}
+
 pub fn init<T>(
     uarte: T,
     pins: Pins,
     res: &'static mut Resources,
-) -> Result<(UplinkDriver<T>, DownlinkDriver<T>, CommandReceiver), Error>
+    game: Sender<'static, GameMessage, GAME_CAPACITY>,
+    shared: Shared,
+) -> Result<
+    (
+        UplinkDriver<T>,
+        DownlinkDriver<T>,
+        CommandReceiver,
+        Notifier,
+        Mirror,
+        UarteInterrupt<T>,
+    ),
+    Error,
+>
 where
     T: Instance,
 {
     let uarte = Uarte::<T>::new(uarte, pins, Parity::EXCLUDED, Baudrate::BAUD115200);
 
-    let (tx, rx) = uarte.split(&mut res.peripheral_tx_buf, &mut res.peripheral_rx_buf)?;
-    let (str_send, str_recv) = res.str_channel.split();
-    let (cmd_send, cmd_recv) = res.cmd_channel.split();
-    let uplink = UplinkDriver::<T>::new(tx, str_recv);
-    let downlink = DownlinkDriver::new(rx, cmd_send);
-    let command_recv = CommandReceiver::new(cmd_recv, str_send);
-    Ok((uplink, downlink, command_recv))
+    let Resources {
+        peripheral_tx_buf,
+        peripheral_rx_buf,
+        str_channel,
+        cmd_channel,
+        wakers,
+    } = res;
+    let wakers: &'static Wakers = wakers;
+    let (tx, rx) = uarte.split(peripheral_tx_buf, peripheral_rx_buf)?;
+    let (str_send, str_recv) = str_channel.split();
+    let (cmd_send, cmd_recv) = cmd_channel.split();
+    let outgoing = Uplink::new(str_send);
+    let uplink = UplinkDriver::<T>::new(tx, str_recv, wakers);
+    let downlink = DownlinkDriver::new(rx, cmd_send, outgoing.clone(), game.clone(), wakers);
+    let notifier = Notifier::new(outgoing.clone(), shared.status);
+    let mirror = Mirror::new(outgoing.clone(), shared.mirror_enabled, shared.status);
+    let command_recv = CommandReceiver::new(cmd_recv, outgoing, game, shared);
+    let interrupt = UarteInterrupt::new(wakers);
+    Ok((uplink, downlink, command_recv, notifier, mirror, interrupt))
 }
@@ -0,0 +1,102 @@
+use super::uplink::{Uplink, UplinkClosed};
+use crate::game::{event::Event, status::SharedStatus};
+
+#[derive(Debug)]
+pub enum DriverError {
+    UplinkReceiverDropped,
+}
+
+impl From<UplinkClosed> for DriverError {
+    fn from(_: UplinkClosed) -> Self {
+        Self::UplinkReceiverDropped
+    }
+}
+
+/// Announces game [`Event`]s on the CLI, i.e. without the user asking for them.
+pub struct Notifier {
+    outgoing: Uplink,
+    status: &'static SharedStatus,
+}
+
+impl Notifier {
+    #[must_use]
+    pub fn new(outgoing: Uplink, status: &'static SharedStatus) -> Self {
+        Self { outgoing, status }
+    }
+
+    pub async fn notify(&mut self, event: Event) -> Result<(), DriverError> {
+        match event {
+            Event::GameOver { score, place } => {
+                write!(
+                    self.outgoing,
+                    "\r\n\
+                    Game over! Final score: {} ({} rows, level {}).\r\n",
+                    score.points, score.rows, score.level
+                )
+                .await?;
+                if let Some(place) = place {
+                    write!(
+                        self.outgoing,
+                        "New high score, number {} in the table!\r\n",
+                        place + 1
+                    )
+                    .await?;
+                }
+                // The new game's seed is only drawn once the game is restarted, so the status
+                // still holds the seed of the game that just ended.
+                if let Some(seed) = self.status.get().seed {
+                    write!(self.outgoing, "Tile seed: {seed}\r\n").await?;
+                }
+                self.outgoing
+                    .write_str("Press button B to play again.\r\n")
+                    .await?;
+            }
+            Event::Restored(score) => {
+                write!(
+                    self.outgoing,
+                    "\r\n\
+                    Restored the game saved before the last reset ({} points, level {}).\r\n\
+                    Press button B while holding button A to resume it, or button B alone (or \
+                    enter `reset`) to start over.\r\n",
+                    score.points, score.level
+                )
+                .await?;
+            }
+            Event::SnapshotsStopped => {
+                self.outgoing
+                    .write_str(
+                        "\r\n\
+                        The game has outgrown its save slot, it won't survive a reset.\r\n",
+                    )
+                    .await?;
+            }
+            Event::CalibrationStarted => {
+                self.outgoing
+                    .write_str(
+                        "\r\n\
+                        Calibrating, hold the board still in your neutral position.\r\n",
+                    )
+                    .await?;
+            }
+            Event::Calibrated { x, z } => {
+                write!(
+                    self.outgoing,
+                    "\r\n\
+                    Calibrated the neutral position to ({x}, {z}).\r\n\
+                    Press button B while holding button A to resume.\r\n"
+                )
+                .await?;
+            }
+            Event::CalibrationFailed => {
+                self.outgoing
+                    .write_str(
+                        "\r\n\
+                        Calibration failed, the board was held too far off level.\r\n",
+                    )
+                    .await?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
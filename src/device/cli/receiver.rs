@@ -1,37 +1,82 @@
 use super::{
-    command::Command,
-    downlink::MAILBOX_CAPACITY as IN_CAPACITY,
-    uplink::{Message, MAILBOX_CAPACITY as OUT_CAPACITY},
+    downlink::{Input, IN_BUFFER_SIZE, MAILBOX_CAPACITY as IN_CAPACITY},
+    uplink::{Uplink, UplinkClosed},
+    Shared,
+};
+use crate::{
+    cli::{
+        command::{Command, CommandError, Import, Scores, Switch},
+        line::PROMPT,
+        mirror::{render, ENTER, LEAVE},
+    },
+    device::storage::Storage,
+    game::{
+        board::Board,
+        driver::MAILBOX_CAPACITY as GAME_CAPACITY,
+        highscore::SharedHighScores,
+        message::Message as GameMessage,
+        record::{ImportError, Recording, SharedRecording},
+        shading::DisplayStyle,
+        speed::Speed,
+        status::{Phase, SharedStatus, Status},
+    },
+    settings::{Key, Settings, SharedSettings},
+};
+use core::{
+    fmt::Write,
+    sync::atomic::{AtomicBool, Ordering},
 };
-use crate::util::StringIter;
-use core::fmt::Write;
-use heapless::String;
 use rtic_sync::channel::{Receiver, Sender};
 
 #[derive(Debug)]
 pub enum DriverError {
     DownlinkSenderDropped,
     UplinkReceiverDropped,
-    Encoding,
+    GameReceiverDropped,
+}
+
+impl From<UplinkClosed> for DriverError {
+    fn from(_: UplinkClosed) -> Self {
+        Self::UplinkReceiverDropped
+    }
 }
 
 pub struct CommandReceiver {
-    incoming: Receiver<'static, Command, IN_CAPACITY>,
-    outgoing: Sender<'static, Message, OUT_CAPACITY>,
+    incoming: Receiver<'static, Input, IN_CAPACITY>,
+    outgoing: Uplink,
+    game: Sender<'static, GameMessage, GAME_CAPACITY>,
+    status: &'static SharedStatus,
+    recording: &'static SharedRecording,
+    mirror_enabled: &'static AtomicBool,
+    settings: &'static SharedSettings,
+    high_scores: &'static SharedHighScores,
+    storage: &'static Storage,
 }
 
 impl CommandReceiver {
     #[must_use]
     pub fn new(
-        incoming: Receiver<'static, Command, IN_CAPACITY>,
-        outgoing: Sender<'static, Message, OUT_CAPACITY>,
+        incoming: Receiver<'static, Input, IN_CAPACITY>,
+        outgoing: Uplink,
+        game: Sender<'static, GameMessage, GAME_CAPACITY>,
+        shared: Shared,
     ) -> Self {
-        Self { incoming, outgoing }
+        Self {
+            incoming,
+            outgoing,
+            game,
+            status: shared.status,
+            recording: shared.recording,
+            mirror_enabled: shared.mirror_enabled,
+            settings: shared.settings,
+            high_scores: shared.high_scores,
+            storage: shared.storage,
+        }
     }
 
     pub async fn run(&mut self) -> Result<(), DriverError> {
         loop {
-            let cmd = self
+            let input = self
                 .incoming
                 .recv()
                 .await
@@ -39,7 +84,12 @@ impl CommandReceiver {
 
             defmt::trace!("Received command, processing it now.");
 
-            self.execute(cmd).await?;
+            match input {
+                Input::Command(cmd) => self.execute(cmd).await?,
+                Input::Invalid(err) => self.report(&err).await?,
+                Input::Overflow => self.report_overflow().await?,
+            }
+            self.reply(PROMPT).await?;
         }
     }
 
@@ -47,60 +97,533 @@ impl CommandReceiver {
         match cmd {
             Command::Help => self.execute_help().await,
             Command::Version => self.execute_version().await,
+            Command::Pause => self.execute_pause().await,
+            Command::Resume => self.execute_resume().await,
+            Command::Score => self.execute_score().await,
+            Command::Seed => self.execute_seed().await,
+            Command::Next => self.execute_next().await,
+            Command::Record(count) => self.execute_record(count).await,
+            Command::Import(import) => self.execute_import(import).await,
+            Command::Replay => self.execute_replay().await,
+            Command::Calibrate => self.execute_calibrate().await,
+            Command::Board => self.execute_board().await,
+            Command::Mirror(switch) => self.execute_mirror(switch).await,
+            // handled by the downlink, as it changes how the input is read
+            Command::Play => Ok(()),
+            Command::Reset => self.execute_reset().await,
+            Command::Speed {
+                period_ms,
+                soft_drop,
+            } => self.execute_speed(period_ms, soft_drop).await,
+            Command::Get(key) => self.execute_get(key).await,
+            Command::Set(key, value) => self.execute_set(key, value).await,
+            Command::Save => self.execute_save().await,
+            Command::Defaults => self.execute_defaults().await,
+            Command::Scores(Scores::List) => self.execute_scores().await,
+            Command::Scores(Scores::Clear) => self.execute_clear_scores().await,
+            Command::Display(style) => self.execute_display(style).await,
         }
     }
 
+    async fn reply(&mut self, text: &str) -> Result<(), DriverError> {
+        Ok(self.outgoing.write_str(text).await?)
+    }
+
+    async fn report(&mut self, err: &CommandError) -> Result<(), DriverError> {
+        write!(
+            self.outgoing,
+            "\r\n\
+            Error: {err}.\r\n\
+            Type 'help' to list the available commands and their arguments.\r\n"
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn report_overflow(&mut self) -> Result<(), DriverError> {
+        write!(
+            self.outgoing,
+            "\r\n\
+            Error: input longer than {IN_BUFFER_SIZE} characters, discarding the line.\r\n"
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn control_game(&mut self, msg: GameMessage) -> Result<(), DriverError> {
+        self.game
+            .send(msg)
+            .await
+            .map_err(|_| DriverError::GameReceiverDropped)
+    }
+
+    /// Sends the control message `msg` and waits for the game to process it, returning the
+    /// status from before and after. Every control message is to be sent this way, otherwise the
+    /// game processing an unawaited one could be taken for processing the next one.
+    async fn await_control(&mut self, msg: GameMessage) -> Result<(Status, Status), DriverError> {
+        debug_assert!(msg.is_control());
+        let before = self.status.get();
+        self.control_game(msg).await?;
+        let after = self.status.control_processed(before.controls).await;
+        Ok((before, after))
+    }
+
     async fn execute_help(&mut self) -> Result<(), DriverError> {
-        let help_text = StringIter::<'_, 32>::from(
-            &"\r\n\
+        self.reply(
+            "\r\n\
             === microtile ===\r\n\
             \r\n\
             available commands:\r\n\
             - help - prints this help message\r\n\
             - ver - prints VCS information\r\n\
+            - pause - pauses the game\r\n\
+            - resume - resumes the paused game\r\n\
+            - score - prints the current game's score\r\n\
+            - seed - prints the seed of the current game's tiles\r\n\
+            - next - prints the upcoming tile\r\n\
+            - record [<count>] - prints the current game's recorded input, optionally only the\r\n\
+              last <count> entries\r\n\
+            - import <line> - imports a line printed by 'record' while the game is paused or\r\n\
+              over, starting with the 'seed:' line, 'replay' then plays the imported game\r\n\
+            - replay - restarts the current game and replays its input\r\n\
+            - calibrate - pauses the game and calibrates the neutral tilt\r\n\
+            - board - prints the board ('o' falling tile, '#' settled cells)\r\n\
+            - mirror [on|off] - toggles a live view of the board atop the terminal\r\n\
+            - play - steers the game from the keyboard, until 'q' is pressed\r\n\
+            - reset - abandons the current game and starts a new one\r\n\
+            - speed [<ms> [<factor>]] - prints or sets the game's speed, i.e. the period of\r\n\
+              its ticks at level 0 (100-5000 ms) and how many times faster it ticks while\r\n\
+              holding button A (1-10)\r\n\
+            - get [<key>] - prints the given setting, or all of them\r\n\
+            - set <key> <value> - changes a setting until the next reset\r\n\
+            - save - keeps the current settings across resets\r\n\
+            - defaults - reverts the settings to their defaults, except for the calibration\r\n\
+            - scores [list|clear] - lists or clears the high scores\r\n\
+            - display [blink|dim|both] - prints or sets how the falling tile stands out: blinking,\r\n\
+              dimmed (see the display.falling setting) or both\r\n\
             \r\n\
             syntax:\r\n\
-            $ <cmd>;
-            where <cmd> is one of above commands\r\n\
+            $ <cmd> [<args>]\r\n\
+            where <cmd> is one of above commands, terminated by enter or ';'\r\n\
+            and <args> its whitespace-separated arguments\r\n\
             \r\n\
             ==================\r\n",
-        );
-
-        for msg in help_text {
-            match msg {
-                Ok(msg) => self
-                    .outgoing
-                    .send(msg)
-                    .await
-                    .map_err(|_| DriverError::UplinkReceiverDropped)?,
-                Err(_) => unreachable!("Hardcoded string should be convertible"),
-            }
-        }
-        Ok(())
+        )
+        .await
     }
 
     async fn execute_version(&mut self) -> Result<(), DriverError> {
-        let mut formatted = String::<128>::new();
         write!(
-            &mut formatted,
+            self.outgoing,
             "\r\n\
             This microtile build is based on '{}'.\r\n",
             env!("VERGEN_GIT_DESCRIBE")
         )
-        .map_err(|_| DriverError::Encoding)?;
+        .await?;
+        Ok(())
+    }
+
+    async fn execute_pause(&mut self) -> Result<(), DriverError> {
+        let (before, after) = self.await_control(GameMessage::Pause).await?;
+        let reply = if before.paused {
+            "\r\nThe game is paused already.\r\n"
+        } else if after.paused {
+            "\r\nGame paused.\r\n"
+        } else if after.phase == Phase::GameOver {
+            "\r\nThe game is over, there is nothing to pause.\r\n"
+        } else {
+            "\r\nThe game could not be paused.\r\n"
+        };
+        self.reply(reply).await
+    }
+
+    async fn execute_resume(&mut self) -> Result<(), DriverError> {
+        let (before, after) = self.await_control(GameMessage::Resume).await?;
+        let reply = if after.calibrating {
+            "\r\nThe tilt is being calibrated, resume once that is done.\r\n"
+        } else if !before.paused {
+            "\r\nThe game is running already.\r\n"
+        } else if !after.paused {
+            "\r\nGame resumed.\r\n"
+        } else {
+            "\r\nThe game could not be resumed.\r\n"
+        };
+        self.reply(reply).await
+    }
+
+    async fn execute_reset(&mut self) -> Result<(), DriverError> {
+        let (before, after) = self.await_control(GameMessage::Reset).await?;
+        let reply = if after.paused || after.phase == Phase::GameOver {
+            "\r\nThe game could not be started over.\r\n"
+        } else if before.calibrating {
+            "\r\nCalibration cancelled, new game started.\r\n"
+        } else {
+            "\r\nNew game started.\r\n"
+        };
+        self.reply(reply).await
+    }
+
+    async fn execute_speed(
+        &mut self,
+        period_ms: Option<u16>,
+        soft_drop: Option<u8>,
+    ) -> Result<(), DriverError> {
+        let current = self.status.get().speed;
+        let speed = Speed {
+            period_ms: period_ms.unwrap_or(current.period_ms),
+            soft_drop: soft_drop.unwrap_or(current.soft_drop),
+        };
+        let changed = speed != current;
+        if changed {
+            self.control_game(GameMessage::SetSpeed(speed)).await?;
+            self.settings.update(|settings| settings.speed = speed);
+        }
+
+        write!(
+            self.outgoing,
+            "\r\nThe game ticks every {} ms at level 0, {} times as often while dropping{}.\r\n",
+            speed.period_ms,
+            speed.soft_drop,
+            if changed { " from now on" } else { "" }
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Makes `settings` the current ones, passing what changed on to the game.
+    async fn apply(&mut self, settings: Settings) -> Result<(), DriverError> {
+        let previous = self
+            .settings
+            .update(|current| core::mem::replace(current, settings));
+        if settings.speed != previous.speed {
+            self.control_game(GameMessage::SetSpeed(settings.speed))
+                .await?;
+        }
+        if settings.tilt != previous.tilt {
+            self.control_game(GameMessage::SetTilt(settings.tilt))
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn execute_get(&mut self, key: Option<Key>) -> Result<(), DriverError> {
+        let settings = self.settings.get();
+        if let Some(key) = key {
+            write!(
+                self.outgoing,
+                "\r\n{} = {}\r\n",
+                key.name(),
+                settings.get(key)
+            )
+            .await?;
+            return Ok(());
+        }
+
+        self.outgoing
+            .write_with(|out| {
+                out.write_str("\r\n")?;
+                for key in Key::ALL {
+                    write!(out, "{} = {}\r\n", key.name(), settings.get(key))?;
+                }
+                write!(out, "display style: {}\r\n", settings.shading.style.name())?;
+                match settings.calibration {
+                    Some((x, z)) => write!(out, "calibration: ({x}, {z})\r\n"),
+                    None => out.write_str("calibration: none\r\n"),
+                }
+            })
+            .await?;
+        Ok(())
+    }
+
+    async fn execute_set(&mut self, key: Key, value: u32) -> Result<(), DriverError> {
+        let mut settings = self.settings.get();
+        if settings.set(key, value).is_err() {
+            // cannot happen, parsing checks the key's range already
+            return self.reply("\r\nError: value out of range.\r\n").await;
+        }
+        self.apply(settings).await?;
+
+        write!(
+            self.outgoing,
+            "\r\n{} = {value}{}\r\n",
+            key.name(),
+            if key == Key::Seed {
+                " (takes effect after the next reset)"
+            } else {
+                ""
+            }
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn execute_save(&mut self) -> Result<(), DriverError> {
+        match self.storage.save_settings(&self.settings.get()) {
+            Ok(()) => self.reply("\r\nSettings saved.\r\n").await,
+            Err(_) => {
+                self.reply("\r\nError: failed to save the settings.\r\n")
+                    .await
+            }
+        }
+    }
+
+    async fn execute_defaults(&mut self) -> Result<(), DriverError> {
+        let mut settings = self.settings.get();
+        settings.reset();
+        self.apply(settings).await?;
+        self.reply("\r\nSettings reverted to their defaults, type 'save' to keep them.\r\n")
+            .await
+    }
 
-        let version_text = StringIter::<'_, 32>::from(&formatted);
+    async fn execute_scores(&mut self) -> Result<(), DriverError> {
+        let high_scores = self.high_scores.read(Clone::clone);
+        if high_scores.entries().is_empty() {
+            return self.reply("\r\nThere are no high scores yet.\r\n").await;
+        }
+
+        self.outgoing
+            .write_with(|out| {
+                out.write_str("\r\n")?;
+                for (place, entry) in high_scores.entries().iter().enumerate() {
+                    write!(
+                        out,
+                        "{}. {} points, {} rows in {}:{:02}",
+                        place + 1,
+                        entry.points,
+                        entry.rows,
+                        entry.duration_secs / 60,
+                        entry.duration_secs % 60
+                    )?;
+                    if let Some(seed) = entry.seed {
+                        write!(out, ", seed {seed}")?;
+                    }
+                    out.write_str("\r\n")?;
+                }
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
 
-        for msg in version_text {
-            match msg {
-                Ok(msg) => self
-                    .outgoing
-                    .send(msg)
+    async fn execute_clear_scores(&mut self) -> Result<(), DriverError> {
+        let high_scores = self.high_scores.update(|high_scores| {
+            high_scores.clear();
+            high_scores.clone()
+        });
+        match self.storage.save_high_scores(&high_scores) {
+            Ok(()) => self.reply("\r\nHigh scores cleared.\r\n").await,
+            Err(_) => {
+                self.reply("\r\nError: failed to save the cleared high scores.\r\n")
                     .await
-                    .map_err(|_| DriverError::UplinkReceiverDropped)?,
-                Err(_) => unreachable!("Hardcoded string should be convertible"),
             }
         }
+    }
+
+    async fn execute_display(&mut self, style: Option<DisplayStyle>) -> Result<(), DriverError> {
+        let shading = self.settings.update(|settings| {
+            if let Some(style) = style {
+                settings.shading.style = style;
+            }
+            settings.shading
+        });
+
+        write!(
+            self.outgoing,
+            "\r\nThe falling tile is shown at brightness {} ({}), the settled cells at {}.\r\n",
+            shading.falling_brightness(true),
+            if shading.style.blinks() {
+                "blinking"
+            } else {
+                "steady"
+            },
+            shading.settled
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn execute_score(&mut self) -> Result<(), DriverError> {
+        let status = self.status.get();
+        write!(
+            self.outgoing,
+            "\r\n\
+            score: {}\r\n\
+            rows: {}\r\n\
+            level: {}\r\n\
+            tiles: {}\r\n\
+            games lost: {}\r\n",
+            status.score.points,
+            status.score.rows,
+            status.score.level,
+            status.score.tiles,
+            status.games_lost
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn execute_seed(&mut self) -> Result<(), DriverError> {
+        match self.status.get().seed {
+            Some(seed) => write!(self.outgoing, "\r\nseed: {seed}\r\n").await?,
+            None => self.reply("\r\nThe tiles are not random.\r\n").await?,
+        }
+        Ok(())
+    }
+
+    async fn execute_next(&mut self) -> Result<(), DriverError> {
+        match self.status.get().next {
+            Some(tile) => write!(self.outgoing, "\r\nnext: {}\r\n", tile.name()).await?,
+            None => self.reply("\r\nThe upcoming tile is unknown.\r\n").await?,
+        }
         Ok(())
     }
+
+    async fn execute_board(&mut self) -> Result<(), DriverError> {
+        let status = self.status.get();
+        self.outgoing
+            .write_with(|out| {
+                write!(out, "\r\nstate: {}", status.phase.name())?;
+                if let Phase::TileFloating { column } = status.phase {
+                    write!(out, " (column {column})")?;
+                }
+                if status.paused {
+                    out.write_str(", paused")?;
+                }
+                out.write_str("\r\n+-----+\r\n")?;
+
+                // Row 0 is the bottom row, so print from top to bottom
+                for row in (0..Board::ROWS).rev() {
+                    out.write_str("|")?;
+                    for column in 0..Board::COLUMNS {
+                        let cell = if status.active.is_set(row, column) {
+                            'o'
+                        } else if status.passive.is_set(row, column) {
+                            '#'
+                        } else {
+                            '.'
+                        };
+                        out.write_char(cell)?;
+                    }
+                    out.write_str("|\r\n")?;
+                }
+                out.write_str("+-----+\r\n")
+            })
+            .await?;
+        Ok(())
+    }
+
+    async fn execute_mirror(&mut self, switch: Option<Switch>) -> Result<(), DriverError> {
+        let enabled = self.mirror_enabled.load(Ordering::Relaxed);
+        let enable = switch.map_or(!enabled, |switch| switch == Switch::On);
+        if enable == enabled {
+            return Ok(());
+        }
+
+        if enable {
+            self.reply(ENTER).await?;
+            // Draw the board right away, the mirror only redraws it once the board changes.
+            let status = self.status.get();
+            self.outgoing.write_with(|out| render(out, &status)).await?;
+            self.mirror_enabled.store(true, Ordering::Relaxed);
+            Ok(())
+        } else {
+            self.mirror_enabled.store(false, Ordering::Relaxed);
+            self.reply(LEAVE).await
+        }
+    }
+
+    async fn execute_record(&mut self, count: Option<usize>) -> Result<(), DriverError> {
+        let (seed, ticks, first, end) = self.recording.read(|recording| {
+            (
+                recording.seed(),
+                recording.ticks(),
+                recording.first(),
+                recording.end(),
+            )
+        });
+
+        self.reply("\r\nseed: ").await?;
+        match seed {
+            Some(seed) => write!(self.outgoing, "{seed}").await?,
+            None => self.reply("-").await?,
+        }
+        write!(
+            self.outgoing,
+            "\r\n\
+            ticks: {ticks}\r\n\
+            entries: {first}..{end}\r\n"
+        )
+        .await?;
+
+        let start = count.map_or(first, |count| first.max(end.saturating_sub(count)));
+        for index in start..end {
+            // The game keeps running while we print, so the oldest entries may get overwritten.
+            let Some(entry) = self.recording.read(|recording| recording.get(index)) else {
+                return self.reply("(truncated)\r\n").await;
+            };
+            write!(self.outgoing, "{entry}\r\n").await?;
+        }
+        Ok(())
+    }
+
+    async fn execute_import(&mut self, import: Import) -> Result<(), DriverError> {
+        // The game records its steps as it goes, they would get mixed up with the imported ones.
+        let status = self.status.get();
+        if !status.paused && status.phase != Phase::GameOver {
+            return self
+                .reply("\r\nPause the game before importing a recording.\r\n")
+                .await;
+        }
+
+        let reply = self.recording.update(|recording| match import {
+            Import::Seed(seed) => {
+                recording.restart(seed);
+                None
+            }
+            Import::Ticks(ticks) => {
+                recording.set_ticks(ticks);
+                None
+            }
+            Import::Entries { first: 0, .. } => None,
+            Import::Entries { .. } => {
+                // there is no replaying a game without its beginning
+                recording.resume(recording.seed());
+                Some("\r\nThe recording is incomplete, it cannot be replayed.\r\n")
+            }
+            Import::Entry(entry) => match recording.import(entry) {
+                Ok(()) => None,
+                Err(ImportError::Full) => Some("\r\nThe recording is full.\r\n"),
+                Err(ImportError::OutOfOrder) => {
+                    Some("\r\nThe entry does not follow the previous one or exceeds the ticks.\r\n")
+                }
+            },
+        });
+        match reply {
+            Some(reply) => self.reply(reply).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn execute_replay(&mut self) -> Result<(), DriverError> {
+        if !self.recording.read(Recording::is_complete) {
+            return self
+                .reply("\r\nThe recording is incomplete and cannot be replayed.\r\n")
+                .await;
+        }
+        self.await_control(GameMessage::Replay).await?;
+        self.reply("\r\nReplaying the recorded game.\r\n").await
+    }
+
+    async fn execute_calibrate(&mut self) -> Result<(), DriverError> {
+        let (before, after) = self.await_control(GameMessage::Calibrate).await?;
+        // the notifier announces a calibration that has started
+        let reply = if before.calibrating {
+            "\r\nThe tilt is being calibrated already.\r\n"
+        } else if after.calibrating {
+            return Ok(());
+        } else {
+            "\r\nThe tilt cannot be calibrated once the game is over.\r\n"
+        };
+        self.reply(reply).await
+    }
 }
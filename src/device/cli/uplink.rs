@@ -1,15 +1,83 @@
-use crate::util::nb_async;
-use core::fmt::Write;
+use super::interrupt::{wait_for, UarteEvent, Wakers};
+use crate::util::{ChunkWriter, Chunks};
+use core::fmt::{Arguments, Result as FmtResult, Write};
 use cortex_m::prelude::_embedded_hal_serial_Write;
 use heapless::String;
 use microbit::hal::uarte::{Error as UarteError, Instance, UarteTx};
-use rtic_sync::channel::Receiver;
+use rtic_sync::channel::{Receiver, Sender, TrySendError};
 
 pub const MESSAGE_LENGTH: usize = 32;
 pub type Message = String<MESSAGE_LENGTH>;
 
 pub const MAILBOX_CAPACITY: usize = 32;
 
+/// The [`UplinkDriver`] is gone, so nothing can be sent anymore.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UplinkClosed;
+
+/// Sending end of the uplink, streaming text of any length into the [`UplinkDriver`]'s mailbox.
+///
+/// Text is formatted with [`write!`], awaiting the result:
+///
+/// ```ignore
+/// write!(uplink, "score: {}\r\n", score.points).await?;
+/// ```
+///
+/// The text is cut into [`Message`]s (see [`Chunks`]), waiting for room in the mailbox as needed.
+#[derive(Clone)]
+pub struct Uplink {
+    outgoing: Sender<'static, Message, MAILBOX_CAPACITY>,
+}
+
+impl Uplink {
+    #[must_use]
+    pub fn new(outgoing: Sender<'static, Message, MAILBOX_CAPACITY>) -> Self {
+        Self { outgoing }
+    }
+
+    /// Sends whatever `format` writes, which has to be the same text each time it is called.
+    pub async fn write_with<F>(&mut self, format: F) -> Result<(), UplinkClosed>
+    where
+        F: FnMut(&mut ChunkWriter<MESSAGE_LENGTH>) -> FmtResult,
+    {
+        for msg in Chunks::new(format) {
+            self.outgoing.send(msg).await.map_err(|_| UplinkClosed)?;
+        }
+        Ok(())
+    }
+
+    /// Sends formatted text, see [`write!`].
+    pub async fn write_fmt(&mut self, args: Arguments<'_>) -> Result<(), UplinkClosed> {
+        self.write_with(|out| out.write_fmt(args)).await
+    }
+
+    pub async fn write_str(&mut self, text: &str) -> Result<(), UplinkClosed> {
+        self.write_with(|out| out.write_str(text)).await
+    }
+
+    /// Sends whatever `format` writes if it fits into the mailbox right away, dropping whatever
+    /// does not fit otherwise. Returns whether all of it has been sent.
+    pub fn try_write_with<F>(&mut self, format: F) -> Result<bool, UplinkClosed>
+    where
+        F: FnMut(&mut ChunkWriter<MESSAGE_LENGTH>) -> FmtResult,
+    {
+        for msg in Chunks::new(format) {
+            match self.outgoing.try_send(msg) {
+                Ok(()) => {}
+                Err(TrySendError::Full(_)) => return Ok(false),
+                Err(TrySendError::NoReceiver(_)) => return Err(UplinkClosed),
+            }
+        }
+        Ok(true)
+    }
+
+    /// Whether the [`UplinkDriver`] has sent everything.
+    #[must_use]
+    pub fn is_idle(&self) -> bool {
+        self.outgoing.is_empty()
+    }
+}
+
 #[derive(Debug)]
 pub enum DriverError {
     SenderDropped,
@@ -23,6 +91,7 @@ where
 {
     tx: UarteTx<T>,
     mailbox: Receiver<'static, Message, MAILBOX_CAPACITY>,
+    wakers: &'static Wakers,
 }
 
 impl<T> UplinkDriver<T>
@@ -30,8 +99,16 @@ where
     T: Instance,
 {
     #[must_use]
-    pub fn new(tx: UarteTx<T>, mailbox: Receiver<'static, Message, MAILBOX_CAPACITY>) -> Self {
-        Self { tx, mailbox }
+    pub fn new(
+        tx: UarteTx<T>,
+        mailbox: Receiver<'static, Message, MAILBOX_CAPACITY>,
+        wakers: &'static Wakers,
+    ) -> Self {
+        Self {
+            tx,
+            mailbox,
+            wakers,
+        }
     }
 
     pub async fn run(&mut self) -> Result<(), DriverError> {
@@ -45,7 +122,7 @@ where
             defmt::trace!("Received string, processing it now.");
 
             write!(self.tx, "{msg}").map_err(|_| DriverError::FormatError)?;
-            nb_async(|| self.tx.flush())
+            wait_for::<T, _, _, _>(UarteEvent::EndTx, self.wakers, || self.tx.flush())
                 .await
                 .map_err(DriverError::UarteError)?;
         }
@@ -1,11 +1,85 @@
-use microbit::display::nonblocking::MicrobitFrame;
+use crate::game::{animation::Animation, shading::ShadedBoard, tile::TileKind};
+use core::cmp::min;
+use heapless::Vec;
+use microbit::display::nonblocking::{GreyscaleImage, MicrobitFrame};
 use microtile_engine::geometry::grid::Grid;
 use tiny_led_matrix::{Frame, Matrix, Render, MAX_BRIGHTNESS};
 
+const IMAGE_ROWS: usize = <<MicrobitFrame as Frame>::Mtx as Matrix>::IMAGE_ROWS;
+const IMAGE_COLUMNS: usize = <<MicrobitFrame as Frame>::Mtx as Matrix>::IMAGE_COLUMNS;
+
+/// Two vertical bars, shown while the game is paused.
+pub const PAUSE_IMAGE: GreyscaleImage = GreyscaleImage::new(&[
+    [0, 0, 0, 0, 0],
+    [0, 9, 0, 9, 0],
+    [0, 9, 0, 9, 0],
+    [0, 9, 0, 9, 0],
+    [0, 0, 0, 0, 0],
+]);
+
+/// A spirit level's bubble, shown while calibrating the tilt.
+pub const CALIBRATION_IMAGE: GreyscaleImage = GreyscaleImage::new(&[
+    [0, 0, 0, 0, 0],
+    [0, 0, 0, 0, 0],
+    [9, 3, 9, 3, 9],
+    [0, 0, 0, 0, 0],
+    [0, 0, 0, 0, 0],
+]);
+
+/// Icons of the tiles, briefly shown to preview the upcoming tile.
+const SQUARE_IMAGE: GreyscaleImage = GreyscaleImage::new(&[
+    [0, 0, 0, 0, 0],
+    [0, 9, 9, 0, 0],
+    [0, 9, 9, 0, 0],
+    [0, 0, 0, 0, 0],
+    [0, 0, 0, 0, 0],
+]);
+const LINE_IMAGE: GreyscaleImage = GreyscaleImage::new(&[
+    [0, 0, 0, 0, 0],
+    [0, 0, 9, 0, 0],
+    [0, 0, 9, 0, 0],
+    [0, 0, 9, 0, 0],
+    [0, 0, 0, 0, 0],
+]);
+const DIAGONAL_IMAGE: GreyscaleImage = GreyscaleImage::new(&[
+    [0, 0, 0, 0, 0],
+    [0, 0, 0, 9, 0],
+    [0, 0, 9, 0, 0],
+    [0, 9, 0, 0, 0],
+    [0, 0, 0, 0, 0],
+]);
+
+/// Alternating frames of a sparkle, celebrating a new high score.
+const SPARKLE_IMAGES: [GreyscaleImage; 2] = [
+    GreyscaleImage::new(&[
+        [0, 0, 0, 0, 0],
+        [0, 0, 5, 0, 0],
+        [0, 5, 9, 5, 0],
+        [0, 0, 5, 0, 0],
+        [0, 0, 0, 0, 0],
+    ]),
+    GreyscaleImage::new(&[
+        [9, 0, 3, 0, 9],
+        [0, 3, 0, 3, 0],
+        [3, 0, 0, 0, 3],
+        [0, 3, 0, 3, 0],
+        [9, 0, 3, 0, 9],
+    ]),
+];
+
+#[must_use]
+pub fn tile_image(tile: TileKind) -> &'static GreyscaleImage {
+    match tile {
+        TileKind::Square => &SQUARE_IMAGE,
+        TileKind::Line => &LINE_IMAGE,
+        TileKind::Diagonal => &DIAGONAL_IMAGE,
+    }
+}
+
 pub struct GridRenderer<'a>(&'a Grid);
 
 impl<'a> GridRenderer<'a> {
-    const ROW_TRANSLATION_OFFSET: usize = <<MicrobitFrame as Frame>::Mtx as Matrix>::IMAGE_ROWS - 1;
+    const ROW_TRANSLATION_OFFSET: usize = IMAGE_ROWS - 1;
 
     #[must_use]
     pub fn new(grid: &'a Grid) -> Self {
@@ -26,3 +100,180 @@ impl<'a> Render for GridRenderer<'a> {
         0
     }
 }
+
+impl Render for ShadedBoard {
+    fn brightness_at(&self, x: usize, y: usize) -> u8 {
+        // see `GridRenderer` for the translation of the rows
+        self.brightness(GridRenderer::ROW_TRANSLATION_OFFSET - y, x)
+    }
+}
+
+/// Renders the current frame of an [`Animation`] below the falling tile of the `live` board.
+pub struct AnimationRenderer<'a> {
+    animation: &'a Animation,
+    live: ShadedBoard,
+}
+
+impl<'a> AnimationRenderer<'a> {
+    #[must_use]
+    pub fn new(animation: &'a Animation, live: ShadedBoard) -> Self {
+        Self { animation, live }
+    }
+}
+
+impl<'a> Render for AnimationRenderer<'a> {
+    fn brightness_at(&self, x: usize, y: usize) -> u8 {
+        self.animation
+            .brightness_below(GridRenderer::ROW_TRANSLATION_OFFSET - y, x, &self.live)
+    }
+}
+
+/// 3x5 glyphs of the digits 0 to 9. Each entry is one row (top row first) with the most
+/// significant bit being the leftmost column.
+const DIGIT_GLYPHS: [[u8; IMAGE_ROWS]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b011, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+const GLYPH_WIDTH: usize = 3;
+// glyph plus one blank column of spacing
+const GLYPH_ADVANCE: usize = GLYPH_WIDTH + 1;
+
+/// A number scrolling by from right to left, moving by one column per [`advance`](Self::advance).
+///
+/// Once the number has left the display, it starts over again.
+pub struct ScrollingNumber {
+    digits: Vec<u8, 10>,
+    offset: usize,
+}
+
+impl ScrollingNumber {
+    #[must_use]
+    pub fn new(number: u32) -> Self {
+        let mut digits = Vec::new();
+        let mut remainder = number;
+        loop {
+            #[allow(clippy::cast_possible_truncation)]
+            digits
+                .push((remainder % 10) as u8)
+                .expect("u32 should have at most 10 digits");
+            remainder /= 10;
+            if remainder == 0 {
+                break;
+            }
+        }
+        digits.reverse();
+        Self { digits, offset: 0 }
+    }
+
+    // The number enters the display from the right, i.e. it is preceded by a display's width of
+    // blank columns.
+    fn width(&self) -> usize {
+        IMAGE_COLUMNS + self.digits.len() * GLYPH_ADVANCE
+    }
+
+    pub fn advance(&mut self) {
+        self.offset = (self.offset + 1) % self.width();
+    }
+}
+
+impl Render for ScrollingNumber {
+    fn brightness_at(&self, x: usize, y: usize) -> u8 {
+        let Some(column) = (self.offset + x).checked_sub(IMAGE_COLUMNS) else {
+            return 0;
+        };
+        let glyph_column = column % GLYPH_ADVANCE;
+        match self.digits.get(column / GLYPH_ADVANCE) {
+            Some(digit) if glyph_column < GLYPH_WIDTH => {
+                let row = DIGIT_GLYPHS[usize::from(*digit)][y];
+                if (row >> (GLYPH_WIDTH - 1 - glyph_column)) & 1 == 1 {
+                    MAX_BRIGHTNESS
+                } else {
+                    0
+                }
+            }
+            _ => 0,
+        }
+    }
+}
+
+/// Sequence played on game over: a curtain closing over the board from the bottom up, followed by
+/// the final score scrolling by over and over again. A new high score sparkles in between.
+pub struct GameOverSequence {
+    step: usize,
+    high_score: bool,
+    score: ScrollingNumber,
+}
+
+impl GameOverSequence {
+    const CURTAIN_STEPS: usize = IMAGE_ROWS;
+    // number of steps to keep the curtain closed before showing the score
+    const HOLD_STEPS: usize = 3;
+    // number of steps to sparkle for after a new high score
+    const SPARKLE_STEPS: usize = 12;
+
+    #[must_use]
+    pub fn new(points: u32) -> Self {
+        Self {
+            step: 0,
+            high_score: false,
+            score: ScrollingNumber::new(points),
+        }
+    }
+
+    /// Celebrates the score as a new high score before showing it.
+    #[must_use]
+    pub fn with_high_score(mut self) -> Self {
+        self.high_score = true;
+        self
+    }
+
+    fn shows_curtain(&self) -> bool {
+        self.step < Self::CURTAIN_STEPS + Self::HOLD_STEPS
+    }
+
+    fn sparkle_steps(&self) -> usize {
+        if self.high_score {
+            Self::SPARKLE_STEPS
+        } else {
+            0
+        }
+    }
+
+    fn shows_sparkle(&self) -> bool {
+        !self.shows_curtain()
+            && self.step < Self::CURTAIN_STEPS + Self::HOLD_STEPS + self.sparkle_steps()
+    }
+
+    pub fn advance(&mut self) {
+        if self.shows_curtain() || self.shows_sparkle() {
+            self.step += 1;
+        } else {
+            self.score.advance();
+        }
+    }
+}
+
+impl Render for GameOverSequence {
+    fn brightness_at(&self, x: usize, y: usize) -> u8 {
+        if self.shows_curtain() {
+            let closed_rows = min(self.step + 1, IMAGE_ROWS);
+            if y >= IMAGE_ROWS - closed_rows {
+                MAX_BRIGHTNESS
+            } else {
+                0
+            }
+        } else if self.shows_sparkle() {
+            SPARKLE_IMAGES[self.step % SPARKLE_IMAGES.len()].brightness_at(x, y)
+        } else {
+            self.score.brightness_at(x, y)
+        }
+    }
+}
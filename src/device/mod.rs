@@ -3,4 +3,5 @@ pub mod button;
 pub mod cli;
 pub mod display;
 pub mod errata;
+pub mod storage;
 pub mod timer;
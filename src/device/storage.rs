@@ -0,0 +1,180 @@
+//! The flash backing the [`Settings`], the [`HighScores`] and the [`Snapshot`] of the game in
+//! progress on the micro:bit.
+
+use crate::{
+    game::{highscore::HighScores, snapshot::Snapshot},
+    settings::{
+        store::{Flash, Store},
+        Settings,
+    },
+};
+use core::{cell::RefCell, slice};
+use critical_section::Mutex;
+use embedded_storage::nor_flash::{NorFlash, ReadNorFlash};
+use microbit::{
+    hal::nvmc::{Nvmc, NvmcError},
+    pac::NVMC,
+};
+
+/// The last six pages of the internal flash, accessed via the NVMC.
+pub struct NvmcFlash {
+    nvmc: Nvmc<NVMC>,
+}
+
+impl NvmcFlash {
+    /// The nRF52833 has 512 KiB of flash organized in 4 KiB pages, the application is nowhere near
+    /// filling it.
+    const BASE_ADDRESS: usize = 0x0007_a000;
+    const PAGES: usize = 6;
+    const SNAPSHOT_PAGE: usize = 0;
+    const HIGH_SCORES_PAGE: usize = 2;
+    const SETTINGS_PAGE: usize = 4;
+    // Before the settings, the last page held nothing but the tilt calibration.
+    const LEGACY_PAGE: usize = 5;
+    const LEGACY_MAGIC: u32 = u32::from_le_bytes(*b"TILT");
+    const LEGACY_RECORD_SIZE: usize = 12;
+
+    #[must_use]
+    pub fn new(nvmc: NVMC) -> Self {
+        // SAFETY: The pages are reserved for the storage, nothing else accesses them.
+        let storage = unsafe {
+            slice::from_raw_parts_mut(
+                Self::BASE_ADDRESS as *mut u8,
+                Self::PAGES * <Self as Flash>::PAGE_SIZE,
+            )
+        };
+        Self {
+            nvmc: Nvmc::new(nvmc, storage),
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn address(page: usize, offset: usize) -> u32 {
+        (page * <Self as Flash>::PAGE_SIZE + offset) as u32
+    }
+
+    /// The tilt calibration stored by firmware predating the settings, if any.
+    pub fn legacy_calibration(&mut self) -> Option<(i16, i16)> {
+        let mut record = [0; Self::LEGACY_RECORD_SIZE];
+        self.read(Self::LEGACY_PAGE, 0, &mut record).ok()?;
+
+        let word =
+            |i: usize| u32::from_le_bytes([record[i], record[i + 1], record[i + 2], record[i + 3]]);
+        let (magic, sample, check) = (word(0), word(4), word(8));
+        if magic != Self::LEGACY_MAGIC || check != !sample {
+            return None;
+        }
+        let [x0, x1, z0, z1] = sample.to_le_bytes();
+        Some((i16::from_le_bytes([x0, x1]), i16::from_le_bytes([z0, z1])))
+    }
+}
+
+impl Flash for NvmcFlash {
+    type Error = NvmcError;
+
+    const PAGE_SIZE: usize = 4096;
+
+    fn read(&mut self, page: usize, offset: usize, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        ReadNorFlash::read(&mut self.nvmc, Self::address(page, offset), bytes)
+    }
+
+    fn write(&mut self, page: usize, offset: usize, bytes: &[u8]) -> Result<(), Self::Error> {
+        NorFlash::write(&mut self.nvmc, Self::address(page, offset), bytes)
+    }
+
+    fn erase(&mut self, page: usize) -> Result<(), Self::Error> {
+        NorFlash::erase(
+            &mut self.nvmc,
+            Self::address(page, 0),
+            Self::address(page + 1, 0),
+        )
+    }
+}
+
+struct Stores {
+    flash: NvmcFlash,
+    settings: Store<Settings>,
+    high_scores: Store<HighScores>,
+    snapshot: Store<Option<Snapshot>>,
+}
+
+/// The [`Store`]s in flash, shared between whoever saves data, e.g. the CLI and the calibration.
+pub struct Storage(Mutex<RefCell<Stores>>);
+
+impl Storage {
+    pub fn open(mut flash: NvmcFlash) -> Result<Self, NvmcError> {
+        let settings = Store::open(&mut flash, NvmcFlash::SETTINGS_PAGE)?;
+        let high_scores = Store::open(&mut flash, NvmcFlash::HIGH_SCORES_PAGE)?;
+        let snapshot = Store::open(&mut flash, NvmcFlash::SNAPSHOT_PAGE)?;
+        Ok(Self(Mutex::new(RefCell::new(Stores {
+            flash,
+            settings,
+            high_scores,
+            snapshot,
+        }))))
+    }
+
+    /// The settings saved last, if any.
+    #[must_use]
+    pub fn settings(&self) -> Option<Settings> {
+        critical_section::with(|cs| self.0.borrow_ref(cs).settings.get().copied())
+    }
+
+    /// The high scores saved last, if any.
+    #[must_use]
+    pub fn high_scores(&self) -> Option<HighScores> {
+        critical_section::with(|cs| self.0.borrow_ref(cs).high_scores.get().cloned())
+    }
+
+    /// See [`Store::save`].
+    pub fn save_settings(&self, settings: &Settings) -> Result<(), NvmcError> {
+        critical_section::with(|cs| {
+            let stores = &mut *self.0.borrow_ref_mut(cs);
+            stores.settings.save(&mut stores.flash, *settings)
+        })
+    }
+
+    /// Saves the settings saved last, or the defaults if there are none, as modified by `f`.
+    pub fn update_settings<F>(&self, f: F) -> Result<(), NvmcError>
+    where
+        F: FnOnce(&mut Settings),
+    {
+        critical_section::with(|cs| {
+            let stores = &mut *self.0.borrow_ref_mut(cs);
+            let mut settings = stores.settings.get().copied().unwrap_or_default();
+            f(&mut settings);
+            stores.settings.save(&mut stores.flash, settings)
+        })
+    }
+
+    /// The snapshot saved last, if any.
+    #[must_use]
+    pub fn snapshot(&self) -> Option<Snapshot> {
+        critical_section::with(|cs| self.0.borrow_ref(cs).snapshot.get().cloned().flatten())
+    }
+
+    /// See [`Store::save`]. Saving the snapshot saved last already is skipped, sparing the flash.
+    pub fn save_snapshot(&self, snapshot: &Option<Snapshot>) -> Result<(), NvmcError> {
+        critical_section::with(|cs| {
+            let stores = &mut *self.0.borrow_ref_mut(cs);
+            if stores
+                .snapshot
+                .get()
+                .map_or(snapshot.is_none(), |saved| saved == snapshot)
+            {
+                return Ok(());
+            }
+            stores.snapshot.save(&mut stores.flash, snapshot.clone())
+        })
+    }
+
+    /// See [`Store::save`].
+    pub fn save_high_scores(&self, high_scores: &HighScores) -> Result<(), NvmcError> {
+        critical_section::with(|cs| {
+            let stores = &mut *self.0.borrow_ref_mut(cs);
+            stores
+                .high_scores
+                .save(&mut stores.flash, high_scores.clone())
+        })
+    }
+}
@@ -1,4 +1,4 @@
-use crate::game::{driver::MAILBOX_CAPACITY, message::Message};
+use crate::game::{clock::PlayClock, driver::MAILBOX_CAPACITY, message::Message, speed::Speed};
 use core::marker::PhantomData;
 use cortex_m::prelude::_embedded_hal_timer_CountDown;
 use microbit::{
@@ -16,30 +16,50 @@ pub struct Stopped;
 
 pub struct GameTickDriver<'a, T, S> {
     command_pipe: Sender<'a, Message, MAILBOX_CAPACITY>,
-    button: BTN_A,
+    button: &'a BTN_A,
     timer: Timer<T, Periodic>,
-    // flag indicating whether the next timer event triggers a tick no matter what state button is in
+    // number of timer events since the last tick, a tick is forced once it reaches the soft drop
+    // multiplier no matter what state button is in
     force_tick: u8,
+    speed: Speed,
+    level: u8,
+    clock: Option<&'a PlayClock>,
     s: PhantomData<S>,
 }
 
-impl<'a, T, S> GameTickDriver<'a, T, S> {
-    const GAME_FORCE_TICK_COUNTER: u8 = 3;
-    const GAME_TICK_FREQ: u32 = 3;
-}
-
 impl<'a, T, S> GameTickDriver<'a, T, S>
 where
     T: Instance,
 {
-    const GAME_TICK_CYCLES: u32 = Timer::<T, Periodic>::TICKS_PER_SECOND / Self::GAME_TICK_FREQ;
+    fn cycles(&self) -> u32 {
+        let period_us = u64::from(self.speed.soft_drop_period_us(self.level));
+        let cycles = u64::from(Timer::<T, Periodic>::TICKS_PER_SECOND) * period_us / 1_000_000;
+        u32::try_from(cycles).unwrap_or(u32::MAX)
+    }
+
+    fn into_state<N>(self, force_tick: u8) -> GameTickDriver<'a, T, N> {
+        GameTickDriver {
+            command_pipe: self.command_pipe,
+            button: self.button,
+            timer: self.timer,
+            force_tick,
+            speed: self.speed,
+            level: self.level,
+            clock: self.clock,
+            s: PhantomData,
+        }
+    }
 }
 
 impl<'a, T> GameTickDriver<'a, T, Stopped>
 where
     T: Instance,
 {
-    pub fn new(mailbox: Sender<'a, Message, MAILBOX_CAPACITY>, button: BTN_A, timer: T) -> Self {
+    pub fn new(
+        mailbox: Sender<'a, Message, MAILBOX_CAPACITY>,
+        button: &'a BTN_A,
+        timer: T,
+    ) -> Self {
         let mut timer = Timer::periodic(timer);
         timer.disable_interrupt();
         timer.reset_event();
@@ -48,26 +68,30 @@ where
             command_pipe: mailbox,
             button,
             timer,
-            force_tick: 1,
+            force_tick: 0,
+            speed: Speed::DEFAULT,
+            level: 0,
+            clock: None,
             s: PhantomData,
         }
     }
 
+    /// Advances `clock` by the time passing between two timer events.
+    #[must_use]
+    pub fn with_clock(mut self, clock: &'a PlayClock) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
     pub fn start(mut self) -> GameTickDriver<'a, T, Started> {
         self.timer.reset_event();
         self.timer.enable_interrupt();
-        self.timer.start(Self::GAME_TICK_CYCLES);
+        self.timer.start(self.cycles());
 
-        GameTickDriver {
-            command_pipe: self.command_pipe,
-            button: self.button,
-            timer: self.timer,
-            force_tick: 1,
-            s: PhantomData,
-        }
+        self.into_state(0)
     }
 
-    pub fn free(self) -> (BTN_A, T) {
+    pub fn free(self) -> (&'a BTN_A, T) {
         (self.button, self.timer.free())
     }
 }
@@ -78,9 +102,13 @@ where
 {
     pub fn handle_timer_event(&mut self) -> Result<(), TrySendError<Message>> {
         self.timer.reset_event();
+        if let Some(clock) = self.clock {
+            clock.advance(self.speed.soft_drop_period_us(self.level));
+        }
 
-        // button is active low
-        if self.force_tick == Self::GAME_FORCE_TICK_COUNTER
+        self.force_tick = self.force_tick.saturating_add(1);
+        // button is active low, the multiplier may have been lowered since the last tick
+        if self.force_tick >= self.speed.soft_drop
             || self
                 .button
                 .is_low()
@@ -89,21 +117,32 @@ where
             self.force_tick = 0;
             self.command_pipe.try_send(Message::TimerTick)
         } else {
-            self.force_tick += 1;
             Ok(())
         }
     }
 
+    /// Adjusts the tick period to the game's current `level`.
+    pub fn set_level(&mut self, level: u8) {
+        self.level = level;
+        self.restart();
+    }
+
+    /// Replaces the base tick period and the soft drop multiplier.
+    pub fn set_speed(&mut self, speed: Speed) {
+        self.speed = speed;
+        self.restart();
+    }
+
+    fn restart(&mut self) {
+        // Restarting the timer resets its counter, so a change may delay the next tick by at most
+        // one period. An event pending meanwhile is handled right after, against the new speed.
+        self.timer.start(self.cycles());
+    }
+
     pub fn stop(mut self) -> GameTickDriver<'a, T, Stopped> {
         self.timer.disable_interrupt();
         self.timer.reset_event();
 
-        GameTickDriver {
-            command_pipe: self.command_pipe,
-            button: self.button,
-            timer: self.timer,
-            force_tick: 1,
-            s: PhantomData,
-        }
+        self.into_state(0)
     }
 }
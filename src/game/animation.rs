@@ -0,0 +1,138 @@
+//! Short keyframe sequences played on the board, e.g. when clearing rows.
+//!
+//! Animations are hardware-independent, so that they can be tested on the host. The display
+//! advances them once per frame it shows (see `device::display`), the game carries on meanwhile.
+
+use super::{board::Board, shading::ShadedBoard};
+
+/// What a [`Keyframe`] does to the animated cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Effect {
+    /// Lights the cells at the given brightness, 0 hiding them.
+    Fill(u8),
+    /// Hides the cells in the given number of leftmost columns, leaving the others settled.
+    Sweep(u8),
+    /// Lights the cells at the given tenths of the settled cells' brightness.
+    Fade(u8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Keyframe {
+    pub effect: Effect,
+    /// Number of frames to show the keyframe for.
+    pub frames: u8,
+}
+
+impl Keyframe {
+    #[must_use]
+    pub const fn new(effect: Effect, frames: u8) -> Self {
+        Self { effect, frames }
+    }
+}
+
+/// Flashes the cleared rows twice, then sweeps them away from the left.
+pub const ROW_CLEAR: &[Keyframe] = &[
+    Keyframe::new(Effect::Fill(9), 1),
+    Keyframe::new(Effect::Fill(0), 1),
+    Keyframe::new(Effect::Fill(9), 1),
+    Keyframe::new(Effect::Sweep(2), 1),
+    Keyframe::new(Effect::Sweep(4), 1),
+];
+
+/// Fades the landed tile in from about the falling tile's brightness to the settled one.
+pub const TILE_LOCK: &[Keyframe] = &[
+    Keyframe::new(Effect::Fade(5), 1),
+    Keyframe::new(Effect::Fade(8), 1),
+];
+
+/// [`Keyframe`]s played on some cells of a board.
+///
+/// The board is frozen at the start of the animation: while it plays, the settled cells are shown
+/// as they were back then, without the falling tile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Animation {
+    keyframes: &'static [Keyframe],
+    // the cells the keyframes apply to
+    cells: Board,
+    board: Board,
+    keyframe: usize,
+    // frames shown of the current keyframe
+    frame: u8,
+}
+
+impl Animation {
+    #[must_use]
+    pub fn new(keyframes: &'static [Keyframe], cells: Board, board: Board) -> Self {
+        Self {
+            keyframes,
+            cells,
+            board,
+            keyframe: 0,
+            frame: 0,
+        }
+    }
+
+    /// Clears the full rows of `board` if there are any, otherwise locks `tile` in place. See
+    /// [`Event::TileLanded`](super::event::Event::TileLanded).
+    #[must_use]
+    pub fn landing(tile: Board, board: Board) -> Self {
+        let rows = board.full_row_cells();
+        if rows.is_empty() {
+            Self::new(TILE_LOCK, tile, board)
+        } else {
+            Self::new(ROW_CLEAR, rows, board)
+        }
+    }
+
+    #[must_use]
+    pub fn is_done(&self) -> bool {
+        self.keyframe >= self.keyframes.len()
+    }
+
+    /// Moves on by a single frame.
+    pub fn advance(&mut self) {
+        let Some(keyframe) = self.keyframes.get(self.keyframe) else {
+            return;
+        };
+        self.frame += 1;
+        if self.frame >= keyframe.frames {
+            self.keyframe += 1;
+            self.frame = 0;
+        }
+    }
+
+    /// Brightness of the given cell in the current frame, the settled cells being lit at
+    /// `settled`. Row 0 is the bottom row, column 0 the leftmost one, see [`Board::is_set`].
+    #[must_use]
+    pub fn brightness(&self, row: usize, column: usize, settled: u8) -> u8 {
+        match self.keyframes.get(self.keyframe) {
+            Some(keyframe) if self.cells.is_set(row, column) => match keyframe.effect {
+                Effect::Fill(brightness) => brightness,
+                Effect::Sweep(columns) if column < usize::from(columns) => 0,
+                Effect::Sweep(_) => settled,
+                Effect::Fade(tenths) => {
+                    let faded = u16::from(settled) * u16::from(tenths.min(10));
+                    // rounding up, so that only fading to 0 hides the cells
+                    #[allow(clippy::cast_possible_truncation)]
+                    {
+                        faded.div_ceil(10) as u8
+                    }
+                }
+            },
+            _ if self.board.is_set(row, column) => settled,
+            _ => 0,
+        }
+    }
+
+    /// Brightness of the given cell in the current frame, with the falling tile of the `live`
+    /// board shown on top. The game carries on while the animation plays, this way the player
+    /// keeps sight of the tile they are steering.
+    #[must_use]
+    pub fn brightness_below(&self, row: usize, column: usize, live: &ShadedBoard) -> u8 {
+        match live.brightness(row, column) {
+            // blinking tiles reveal the animation in their unlit frames
+            falling if falling > 0 && live.active.is_set(row, column) => falling,
+            _ => self.brightness(row, column, live.settled()),
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use microtile_engine::geometry::grid::Grid;
+
+/// Compact copy of a [`Grid`], storing one bit per cell.
+///
+/// Other than [`Grid`], a [`Board`] is [`Copy`] and can hence be handed around freely, e.g. to
+/// report the game's state to the CLI.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Board(u32);
+
+impl Board {
+    pub const ROWS: usize = 5;
+    pub const COLUMNS: usize = 5;
+
+    const FULL_ROW: u32 = (1 << Self::COLUMNS) - 1;
+    const ALL: u32 = (1 << (Self::ROWS * Self::COLUMNS)) - 1;
+
+    #[must_use]
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// One bit per cell, starting with the bottom row's leftmost cell.
+    #[must_use]
+    pub const fn bits(&self) -> u32 {
+        self.0
+    }
+
+    /// Inverse of [`bits`](Self::bits), `None` if any bit beyond the board is set.
+    #[must_use]
+    pub const fn from_bits(bits: u32) -> Option<Self> {
+        if bits & !Self::ALL == 0 {
+            Some(Self(bits))
+        } else {
+            None
+        }
+    }
+
+    const fn index(row: usize, column: usize) -> usize {
+        row * Self::COLUMNS + column
+    }
+
+    /// Row 0 is the bottom row, column 0 the leftmost one. Cells outside the board are never set.
+    #[must_use]
+    pub fn is_set(&self, row: usize, column: usize) -> bool {
+        row < Self::ROWS && column < Self::COLUMNS && self.0 & (1 << Self::index(row, column)) != 0
+    }
+
+    pub fn set(&mut self, row: usize, column: usize) {
+        if row < Self::ROWS && column < Self::COLUMNS {
+            self.0 |= 1 << Self::index(row, column);
+        }
+    }
+
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    #[must_use]
+    pub fn count(&self) -> u32 {
+        self.0.count_ones()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    fn is_row_full(self, row: usize) -> bool {
+        (self.0 >> Self::index(row, 0)) & Self::FULL_ROW == Self::FULL_ROW
+    }
+
+    #[must_use]
+    pub fn full_rows(&self) -> u32 {
+        let full = (0..Self::ROWS).filter(|row| self.is_row_full(*row)).count();
+        #[allow(clippy::cast_possible_truncation)]
+        {
+            full as u32
+        }
+    }
+
+    /// The cells of the full rows, i.e. those about to be cleared.
+    #[must_use]
+    pub fn full_row_cells(&self) -> Self {
+        let cells = (0..Self::ROWS)
+            .filter(|row| self.is_row_full(*row))
+            .fold(0, |cells, row| {
+                cells | (Self::FULL_ROW << Self::index(row, 0))
+            });
+        Self(cells)
+    }
+}
+
+impl From<&Grid> for Board {
+    fn from(grid: &Grid) -> Self {
+        let mut board = Self::empty();
+        for row in 0..Self::ROWS {
+            for column in 0..Self::COLUMNS {
+                if let Ok(true) = grid.is_element_set(row, column) {
+                    board.set(row, column);
+                }
+            }
+        }
+        board
+    }
+}
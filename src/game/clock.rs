@@ -0,0 +1,96 @@
+use core::{
+    cell::Cell,
+    fmt::{Debug, Formatter, Result as FmtResult},
+};
+use critical_section::Mutex;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Elapsed {
+    running: bool,
+    micros: u64,
+    // all the time the clock has been advanced by, running or not
+    uptime: u64,
+}
+
+/// Time spent playing the current game, i.e. excluding pauses.
+///
+/// Whoever generates the ticks [`advance`](Self::advance)s the clock as time goes by, whereas
+/// [`GameDriver`](super::driver::GameDriver) starts and stops it. Stopped or not, the clock keeps
+/// track of the [uptime](Self::uptime_micros) as well, which times e.g. the grace period after a
+/// game over.
+pub struct PlayClock(Mutex<Cell<Elapsed>>);
+
+impl PlayClock {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(Mutex::new(Cell::new(Elapsed {
+            running: false,
+            micros: 0,
+            uptime: 0,
+        })))
+    }
+
+    /// Accounts for `micros` microseconds having passed, as play time unless the clock is stopped.
+    pub fn advance(&self, micros: u32) {
+        self.update(|elapsed| {
+            elapsed.uptime = elapsed.uptime.saturating_add(micros.into());
+            if elapsed.running {
+                elapsed.micros = elapsed.micros.saturating_add(micros.into());
+            }
+        });
+    }
+
+    /// Starts over from zero.
+    pub fn restart(&self) {
+        self.restart_at(0);
+    }
+
+    /// Starts over from `secs` seconds, e.g. when resuming a saved game.
+    pub fn restart_at(&self, secs: u32) {
+        self.update(|elapsed| {
+            elapsed.running = true;
+            elapsed.micros = u64::from(secs) * 1_000_000;
+        });
+    }
+
+    pub fn set_running(&self, running: bool) {
+        self.update(|elapsed| elapsed.running = running);
+    }
+
+    #[must_use]
+    pub fn elapsed_secs(&self) -> u32 {
+        let micros = critical_section::with(|cs| self.0.borrow(cs).get().micros);
+        u32::try_from(micros / 1_000_000).unwrap_or(u32::MAX)
+    }
+
+    /// Microseconds the clock has been advanced by since its creation, including pauses.
+    #[must_use]
+    pub fn uptime_micros(&self) -> u64 {
+        critical_section::with(|cs| self.0.borrow(cs).get().uptime)
+    }
+
+    fn update<F>(&self, f: F)
+    where
+        F: FnOnce(&mut Elapsed),
+    {
+        critical_section::with(|cs| {
+            let cell = self.0.borrow(cs);
+            let mut elapsed = cell.get();
+            f(&mut elapsed);
+            cell.set(elapsed);
+        });
+    }
+}
+
+impl Default for PlayClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for PlayClock {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let elapsed = critical_section::with(|cs| self.0.borrow(cs).get());
+        f.debug_tuple("PlayClock").field(&elapsed).finish()
+    }
+}
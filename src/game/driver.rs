@@ -1,22 +1,46 @@
-use super::{message::Message, tile::TileProducer};
-use core::{
-    f32::consts::{FRAC_PI_2, PI},
-    fmt::Debug,
-    ops::FnOnce,
+use super::{
+    board::Board,
+    clock::PlayClock,
+    event::{Event, Listener},
+    highscore::{Entry, SharedHighScores},
+    message::Message,
+    record::{Recording, SharedRecording},
+    score::Score,
+    snapshot::{Journal, SharedSnapshot, Snapshot, Step},
+    speed::Speed,
+    status::{Phase, SharedStatus},
+    tile::{TileKind, TileProducer},
+    tilt::{Calibrator, Tilt},
 };
+use crate::log;
+use core::{fmt::Debug, ops::FnOnce};
 use either::Either;
-use micromath::F32Ext;
-use microtile_engine::gameplay::game::{Game, Observer, ProcessRows, TileFloating, TileNeeded};
+use microtile_engine::{
+    gameplay::game::{Game, Observer, ProcessRows, TileFloating, TileNeeded},
+    geometry::grid::Grid,
+};
 use rtic_sync::channel::{ReceiveError, Receiver};
 
+#[derive(Debug)]
 pub enum DriverError {
     SenderDropped,
 }
 
+/// Transitions of [`State`] that are relevant for keeping score and animating the board.
+#[derive(Clone, Copy)]
+enum Transition {
+    TileLanded,
+    TilePlaced,
+    GameOver,
+    GameStarted,
+}
+
 enum State<O, P> {
     TileNeeded(Game<TileNeeded, O>, P),
     TileFloating(Game<TileFloating, O>, P),
     ProcessRows(Game<ProcessRows, O>, P),
+    /// The previous game is over, the contained one is waiting to be started.
+    GameOver(Game<TileNeeded, O>, P),
 }
 
 impl<O, P> State<O, P>
@@ -24,32 +48,85 @@ where
     O: Observer + Debug,
     P: TileProducer,
 {
-    fn tick(self) -> Self {
+    fn tick(self) -> (Self, Option<Transition>) {
         match self {
             State::TileFloating(game, p) => match game.descend_tile() {
-                Either::Left(game) => State::TileFloating(game, p),
-                Either::Right(game) => State::ProcessRows(game, p),
+                Either::Left(game) => (State::TileFloating(game, p), None),
+                Either::Right(game) => (State::ProcessRows(game, p), Some(Transition::TileLanded)),
             },
             State::ProcessRows(game, p) => match game.process_row() {
-                Either::Left(game) => State::ProcessRows(game, p),
-                Either::Right(game) => State::TileNeeded(game, p),
+                Either::Left(game) => (State::ProcessRows(game, p), None),
+                Either::Right(game) => (State::TileNeeded(game, p), None),
             },
             State::TileNeeded(game, mut p) => match game.place_tile(p.generate_tile()) {
-                Either::Left(game) => State::TileFloating(game, p),
+                Either::Left(game) => (State::TileFloating(game, p), Some(Transition::TilePlaced)),
                 Either::Right(mut game) => {
-                    defmt::info!("Game over, please try again!");
+                    log::info!("Game over, press button B to try again!");
                     let o = game
                         .clear_observer()
                         .expect("game should have an observer set");
                     let mut game = Game::default();
                     game.set_observer(o)
                         .expect("newly initialized game should not have observer set");
-                    let game = game
-                        .place_tile(p.generate_tile())
-                        .expect_left("first tile should not end game");
-                    State::TileFloating(game, p)
+                    (State::GameOver(game, p), Some(Transition::GameOver))
                 }
             },
+            State::GameOver(..) => (self, None),
+        }
+    }
+
+    fn restart(self) -> (Self, Option<Transition>) {
+        match self {
+            State::GameOver(game, mut p) => {
+                p.start_game();
+                let game = game
+                    .place_tile(p.generate_tile())
+                    .expect_left("first tile should not end game");
+                (State::TileFloating(game, p), Some(Transition::GameStarted))
+            }
+            _ => {
+                log::debug!("Ignoring restart due to inapplicable state.");
+                (self, None)
+            }
+        }
+    }
+
+    /// Abandons the current game in favour of a new one, seeded with `seed` if given.
+    fn reset(self, seed: Option<u32>) -> (Self, Transition) {
+        let (o, mut p) = match self {
+            State::TileNeeded(mut game, p) | State::GameOver(mut game, p) => {
+                (game.clear_observer(), p)
+            }
+            State::TileFloating(mut game, p) => (game.clear_observer(), p),
+            State::ProcessRows(mut game, p) => (game.clear_observer(), p),
+        };
+        let o = o.expect("game should have an observer set");
+
+        match seed {
+            Some(seed) => p.start_game_with_seed(seed),
+            None => p.start_game(),
+        }
+        let mut game = Game::default();
+        game.set_observer(o)
+            .expect("newly initialized game should not have observer set");
+        let game = game
+            .place_tile(p.generate_tile())
+            .expect_left("first tile should not end game");
+        (State::TileFloating(game, p), Transition::GameStarted)
+    }
+}
+
+impl<O, P> State<O, P> {
+    fn is_over(&self) -> bool {
+        matches!(self, State::GameOver(..))
+    }
+
+    fn producer(&self) -> &P {
+        match self {
+            State::TileNeeded(_, p)
+            | State::TileFloating(_, p)
+            | State::ProcessRows(_, p)
+            | State::GameOver(_, p) => p,
         }
     }
 }
@@ -58,20 +135,49 @@ impl<O, P> State<O, P>
 where
     O: Observer + Debug,
 {
+    fn phase(&self) -> Phase {
+        match self {
+            State::TileNeeded(..) => Phase::TileNeeded,
+            State::TileFloating(game, _) => Phase::TileFloating {
+                column: game.tile_column(),
+            },
+            State::ProcessRows(..) => Phase::ProcessRows,
+            State::GameOver(..) => Phase::GameOver,
+        }
+    }
+
     fn rotate(self) -> Self {
         if let State::TileFloating(mut game, p) = self {
             if game.rotate_tile().is_err() {
-                defmt::debug!("Ignoring invalid rotation.");
+                log::debug!("Ignoring invalid rotation.");
             }
             State::TileFloating(game, p)
         } else {
-            defmt::debug!("Ignoring rotation due to inapplicable state.");
+            log::debug!("Ignoring rotation due to inapplicable state.");
+            self
+        }
+    }
+
+    /// Moves the floating tile by a single column, to the left if `left` is set.
+    fn shift(self, left: bool) -> Self {
+        if let State::TileFloating(mut game, p) = self {
+            let moved = if left {
+                game.move_tile_left()
+            } else {
+                game.move_tile_right()
+            };
+            if moved.is_err() {
+                log::debug!("Ignoring invalid move");
+            }
+            Self::TileFloating(game, p)
+        } else {
+            log::debug!("Ignoring horizontal movement due to inapplicable state");
             self
         }
     }
 
     fn move_to(self, column: u8) -> Self {
-        defmt::debug!("column: {}", column);
+        log::debug!("column: {}", column);
 
         if let State::TileFloating(mut game, p) = self {
             let difference =
@@ -80,29 +186,122 @@ where
             for _ in 1..=difference.abs() {
                 if difference < 0 {
                     if game.move_tile_left().is_err() {
-                        defmt::debug!("Ignoring invalid move to the left");
+                        log::debug!("Ignoring invalid move to the left");
                         break;
                     }
                 } else {
                     #[allow(clippy::collapsible_else_if)] // keeping this for matching source layout
                     if game.move_tile_right().is_err() {
-                        defmt::debug!("Ignoring invalid move to the right");
+                        log::debug!("Ignoring invalid move to the right");
                         break;
                     }
                 }
             }
             Self::TileFloating(game, p)
         } else {
-            defmt::debug!("Ignoring horizontal movement due to inapplicable state");
+            log::debug!("Ignoring horizontal movement due to inapplicable state");
             self
         }
     }
 }
 
+/// What is left of the grace period after a game over, during which the next game may not be
+/// started, so that the player does not start it by accident.
+#[derive(Debug, Clone, Copy)]
+enum Grace {
+    /// Ticks to go, for lack of a clock. The ticks speed up with the level and the soft drop, so
+    /// they only roughly tell the time.
+    Ticks(u8),
+    /// Uptime at which the period ends, see [`PlayClock::uptime_micros`].
+    Until(u64),
+}
+
+impl Grace {
+    const TICKS: u8 = 3;
+    const MICROS: u64 = 3_000_000;
+
+    fn start(clock: Option<&PlayClock>) -> Self {
+        match clock {
+            Some(clock) => Grace::Until(clock.uptime_micros().saturating_add(Self::MICROS)),
+            None => Grace::Ticks(Self::TICKS),
+        }
+    }
+
+    fn tick(&mut self) {
+        if let Grace::Ticks(ticks) = self {
+            *ticks = ticks.saturating_sub(1);
+        }
+    }
+
+    fn is_over(self, clock: Option<&PlayClock>) -> bool {
+        match self {
+            Grace::Ticks(ticks) => ticks == 0,
+            // a clock is either set from the start or not at all
+            Grace::Until(end) => !clock.is_some_and(|clock| clock.uptime_micros() < end),
+        }
+    }
+}
+
+/// Progress of a replay, see [`Message::Replay`].
+#[derive(Debug, Clone, Copy, Default)]
+struct Replay {
+    // index of the next recorded entry to take
+    entry: usize,
+    // ticks replayed so far
+    ticks: u32,
+}
+
+/// [`Observer`] mirroring the boards into [`SharedStatus`] before passing them on.
+#[derive(Debug)]
+struct Tracker<'a, O> {
+    observer: O,
+    status: &'a SharedStatus,
+}
+
+impl<'a, O> Observer for Tracker<'a, O>
+where
+    O: Observer,
+{
+    fn signal_board_changed(&self, active: Grid, passive: Grid) {
+        self.status.update(|status| {
+            status.active = Board::from(&active);
+            status.passive = Board::from(&passive);
+        });
+        self.observer.signal_board_changed(active, passive);
+    }
+}
+
 pub struct GameDriver<'a, O, P> {
     // `None` value is used to implement [Jone's trick](https://matklad.github.io/2019/07/25/unsafe-as-a-type-system.html),
     // any user-facing `None` is considered a bug. I.e. the user may assume to always interact with a `Some(...)`.
-    s: Option<State<O, P>>,
+    s: Option<State<Tracker<'a, O>, P>>,
+    // While paused, the game does not advance, i.e. ticks and tile movements are ignored.
+    paused: bool,
+    // Set while the game restored from a snapshot waits for the player to resume or decline it.
+    restored: bool,
+    // Only meaningful while the game is over.
+    grace: Grace,
+    score: Score,
+    games_lost: u32,
+    listener: O,
+    status: &'a SharedStatus,
+    recording: Option<&'a SharedRecording>,
+    clock: Option<&'a PlayClock>,
+    high_scores: Option<&'a SharedHighScores>,
+    snapshots: Option<&'a SharedSnapshot>,
+    // Steps of the current game, as long as snapshots are taken and the steps fit.
+    journal: Option<Journal>,
+    tilt: Tilt,
+    // The column selected by the latest accelerometer sample.
+    tilt_column: Option<u8>,
+    // Set by explicit moves, which the tilt must not undo until it selects another column.
+    steered_by_moves: bool,
+    // While calibrating, the game is paused and the accelerometer samples feed the calibrator.
+    calibration: Option<Calibrator>,
+    // While replaying, the recorded steps are taken on each tick instead of the live input.
+    replay: Option<Replay>,
+    // see `Status::controls`
+    controls: u32,
     mailbox: Receiver<'a, Message, MAILBOX_CAPACITY>,
 }
 
@@ -116,101 +315,667 @@ impl<'a, O, P> GameDriver<'a, O, P>
 where
     O: Observer + Debug,
 {
-    // Thresholds when measuring the angle from the vertical z-axis
-    const COLUMN_0_THRESHOLD_UNCOMP: f32 = -PI * 3.0 / 16.0;
-    const COLUMN_1_THRESHOLD_UNCOMP: f32 = -PI / 16.0;
-    const COLUMN_2_THRESHOLD_UNCOMP: f32 = -Self::COLUMN_1_THRESHOLD_UNCOMP;
-    const COLUMN_3_THRESHOLD_UNCOMP: f32 = -Self::COLUMN_0_THRESHOLD_UNCOMP;
-
-    // Thresholds when measuring the angle using the values coming from the
-    // accelerometer (that is from negative x-axis, or measured from the vertical
-    // z-axis with an offset of pi/2)
-    const COLUMN_0_THRESHOLD: f32 = Self::COLUMN_0_THRESHOLD_UNCOMP - FRAC_PI_2;
-    const COLUMN_1_THRESHOLD: f32 = Self::COLUMN_1_THRESHOLD_UNCOMP - FRAC_PI_2;
-    const COLUMN_2_THRESHOLD: f32 = Self::COLUMN_2_THRESHOLD_UNCOMP - FRAC_PI_2;
-    const COLUMN_3_THRESHOLD: f32 = Self::COLUMN_3_THRESHOLD_UNCOMP - FRAC_PI_2;
-
-    fn convert_accel_to_column(x: i16, z: i16) -> u8 {
-        let x: f32 = x.into();
-        let z: f32 = z.into();
-        let angle = z.atan2(x); // think + FRAC_PI_2, but this offset is
-                                //compensated in below thresholds
-        if angle < Self::COLUMN_0_THRESHOLD {
-            0
-        } else if angle < Self::COLUMN_1_THRESHOLD {
-            1
-        } else if angle < Self::COLUMN_2_THRESHOLD {
-            2
-        } else if angle < Self::COLUMN_3_THRESHOLD {
-            3
-        } else {
-            4
-        }
-    }
-
-    fn map_state<F>(&mut self, f: F)
+    fn map_state_with<F, R>(&mut self, f: F) -> R
     where
-        F: FnOnce(State<O, P>) -> State<O, P>,
+        F: FnOnce(State<Tracker<'a, O>, P>) -> (State<Tracker<'a, O>, P>, R),
     {
         // We apply
         // [Jone's trick](https://matklad.github.io/2019/07/25/unsafe-as-a-type-system.html) in
         // here to transparently promote a borrowed to an owned state without cloning. The borrowed
         // state is taken from the borrowed &self.
-        let state = self.s.take();
-        if state.is_none() {
+        let Some(state) = self.s.take() else {
             unreachable!("GameDriver should always be in a defined state");
+        };
+
+        let (state, res) = f(state);
+        self.s = Some(state);
+        res
+    }
+
+    fn map_state<F>(&mut self, f: F)
+    where
+        F: FnOnce(State<Tracker<'a, O>, P>) -> State<Tracker<'a, O>, P>,
+    {
+        self.map_state_with(|s| (f(s), ()));
+    }
+}
+
+impl<'a, O, P> GameDriver<'a, O, P>
+where
+    O: Observer + Listener + Debug,
+    P: TileProducer,
+{
+    fn is_over(&self) -> bool {
+        self.s.as_ref().is_some_and(State::is_over)
+    }
+
+    /// Number of tiles placed between two snapshots, see [`with_snapshots`](Self::with_snapshots).
+    const SNAPSHOT_INTERVAL: u32 = 10;
+
+    fn pause(&mut self) {
+        if self.paused {
+            log::debug!("Ignoring pause, game is paused already.");
+        } else if self.is_over() {
+            log::debug!("Ignoring pause, game is over.");
+        } else {
+            self.paused = true;
+            self.listener.signal_event(Event::Paused);
+            self.take_snapshot();
         }
+    }
 
-        self.s = state.map(f);
+    fn resume(&mut self) {
+        if self.paused {
+            self.paused = false;
+            self.restored = false;
+            self.listener.signal_event(Event::Resumed);
+        } else {
+            log::debug!("Ignoring resumption, game is running already.");
+        }
+    }
+
+    fn toggle_pause(&mut self) {
+        if self.paused {
+            self.resume();
+        } else {
+            self.pause();
+        }
+    }
+
+    fn keep_score(&mut self, transition: Transition) {
+        match transition {
+            Transition::TileLanded => {
+                // The tile has landed but the rows have not been processed yet, i.e. the tile is
+                // still part of the last signalled boards.
+                let status = self.status.get();
+                let rows = status.active.union(&status.passive).full_rows();
+                if self.score.add_rows(rows) {
+                    log::info!("Advancing to level {}.", self.score.level);
+                    self.listener
+                        .signal_event(Event::LevelChanged(self.score.level));
+                }
+            }
+            Transition::TilePlaced => self.score.add_tile(),
+            Transition::GameOver => {
+                log::info!("Game over with a score of {}.", self.score.points);
+                self.games_lost = self.games_lost.saturating_add(1);
+                self.grace = Grace::start(self.clock);
+                let place = self.enter_high_score();
+                self.listener.signal_event(Event::GameOver {
+                    score: self.score,
+                    place,
+                });
+            }
+            Transition::GameStarted => {
+                let level = self.score.level;
+                self.score = Score::new();
+                // the new game has been started with a tile in place already
+                self.score.add_tile();
+                if level != self.score.level {
+                    self.listener
+                        .signal_event(Event::LevelChanged(self.score.level));
+                }
+                self.listener.signal_event(Event::GameStarted);
+                if let Some(clock) = self.clock {
+                    clock.restart();
+                }
+            }
+        }
+    }
+
+    /// Enters the game that just ended into the high scores, returning its place if it made it.
+    fn enter_high_score(&self) -> Option<usize> {
+        // A replayed game has been entered already, when it was played originally.
+        if self.replay.is_some() {
+            return None;
+        }
+        let high_scores = self.high_scores?;
+        let duration = self.clock.map_or(0, PlayClock::elapsed_secs);
+        let entry = Entry::new(&self.score, duration, self.seed());
+        let place = high_scores.update(|scores| scores.insert(entry));
+        if let Some(place) = place {
+            log::info!("New high score, entering it at place {}.", place);
+        }
+        place
+    }
+
+    fn next_tile(&self) -> Option<TileKind> {
+        self.s
+            .as_ref()
+            .and_then(|state| state.producer().peek())
+            .map(TileKind::from)
+    }
+
+    fn preview(&self, transition: Transition) {
+        if let Transition::TilePlaced | Transition::GameStarted = transition {
+            if let Some(next) = self.next_tile() {
+                self.listener.signal_event(Event::NextTile(next));
+            }
+        }
+    }
+
+    fn process(&mut self, transition: Transition) {
+        self.keep_score(transition);
+        self.preview(transition);
+        match transition {
+            Transition::TilePlaced if self.score.tiles % Self::SNAPSHOT_INTERVAL == 0 => {
+                self.take_snapshot();
+            }
+            Transition::GameOver => {
+                self.journal = None;
+                self.share_snapshot(None);
+            }
+            Transition::TileLanded => {
+                let status = self.status.get();
+                self.listener.signal_event(Event::TileLanded {
+                    tile: status.active,
+                    board: status.active.union(&status.passive),
+                });
+            }
+            Transition::GameStarted => {
+                // A recorded game has to be replayable without knowing about the previous game's
+                // samples.
+                self.tilt.reset();
+                self.tilt_column = None;
+                self.steered_by_moves = false;
+                self.restart_recording();
+                self.journal = self.snapshots.map(|_| Journal::new());
+                self.share_snapshot(None);
+            }
+            Transition::TilePlaced => {}
+        }
+    }
+
+    fn tile_column(&self) -> Option<u8> {
+        match self.s.as_ref().map(State::phase) {
+            Some(Phase::TileFloating { column }) => Some(column),
+            _ => None,
+        }
+    }
+
+    fn journal(&mut self, step: Step) {
+        let Some(journal) = self.journal.as_mut() else {
+            return;
+        };
+        if journal.push(step).is_err() {
+            log::warn!("The game has outgrown its journal, no more snapshots are taken of it.");
+            self.journal = None;
+            // The latest snapshot would resume the game at some earlier point without notice.
+            self.share_snapshot(None);
+            self.listener.signal_event(Event::SnapshotsStopped);
+        }
+    }
+
+    /// Keeps track of `step` in the journal as well as in the recording.
+    fn track(&mut self, step: Step) {
+        self.record(step);
+        self.journal(step);
+    }
+
+    /// Tracks the move of the floating tile, provided it has left column `from`.
+    fn track_move(&mut self, from: Option<u8>) {
+        if let Some(column) = self.tile_column().filter(|column| Some(*column) != from) {
+            self.track(Step::MoveTo(column));
+        }
+    }
+
+    fn take_snapshot(&self) {
+        let (Some(journal), Some(seed)) = (self.journal.as_ref(), self.seed()) else {
+            return;
+        };
+        let status = self.status.get();
+        self.share_snapshot(Some(Snapshot {
+            seed,
+            score: self.score,
+            play_secs: self.clock.map_or(0, PlayClock::elapsed_secs),
+            active: status.active,
+            passive: status.passive,
+            journal: journal.clone(),
+        }));
+    }
+
+    fn share_snapshot(&self, snapshot: Option<Snapshot>) {
+        let Some(snapshots) = self.snapshots else {
+            return;
+        };
+        let changed = snapshots.update(|shared| {
+            let changed = *shared != snapshot;
+            *shared = snapshot;
+            changed
+        });
+        if changed {
+            self.listener.signal_event(Event::SnapshotTaken);
+        }
+    }
+
+    /// Resumes the game the shared snapshot has been taken of, if any. Should its journal not
+    /// reproduce the game, e.g. because the rules have changed since, a new game is started
+    /// instead.
+    fn resume_snapshot(&mut self) {
+        let Some(snapshot) = self
+            .snapshots
+            .and_then(|snapshots| snapshots.read(Clone::clone))
+        else {
+            return;
+        };
+
+        log::info!("Resuming the game saved before the last reset.");
+        self.map_state_with(|s| s.reset(Some(snapshot.seed)));
+        let mut tiles = 1;
+        for step in snapshot.journal.steps() {
+            let transition = match step {
+                Step::Tick => self.map_state_with(State::tick),
+                Step::Rotate => {
+                    self.map_state(State::rotate);
+                    None
+                }
+                Step::MoveTo(column) => {
+                    self.map_state(|s| s.move_to(column));
+                    None
+                }
+            };
+            if let Some(Transition::TilePlaced) = transition {
+                tiles += 1;
+            }
+        }
+
+        let status = self.status.get();
+        if self.is_over()
+            || tiles != snapshot.score.tiles
+            || status.active != snapshot.active
+            || status.passive != snapshot.passive
+        {
+            log::warn!("Discarding the saved game, its journal does not reproduce it.");
+            let transition = self.map_state_with(|s| s.reset(None));
+            self.process(transition);
+            return;
+        }
+
+        self.score = snapshot.score;
+        if self.score.level != 0 {
+            self.listener
+                .signal_event(Event::LevelChanged(self.score.level));
+        }
+        self.tilt.reset();
+        self.tilt_column = None;
+        self.steered_by_moves = false;
+        if let Some(recording) = self.recording {
+            recording.update(|recording| recording.resume(Some(snapshot.seed)));
+        }
+        if let Some(clock) = self.clock {
+            clock.restart_at(snapshot.play_secs);
+        }
+        self.journal = Some(snapshot.journal);
+        // It is up to the player whether to go on or to start over.
+        self.paused = true;
+        self.restored = true;
+        self.listener.signal_event(Event::Restored(self.score));
+    }
+
+    fn start_calibration(&mut self) {
+        if self.is_over() {
+            log::debug!("Ignoring calibration, game is over.");
+            return;
+        }
+        // Hold the game while the player is busy holding the board still. There is no need to
+        // signal the pause separately, the calibration implies it.
+        self.paused = true;
+        self.calibration = Some(Calibrator::new());
+        self.listener.signal_event(Event::CalibrationStarted);
+    }
+
+    fn calibrate(&mut self, x: i16, z: i16) {
+        let Some(calibrator) = self.calibration.as_mut() else {
+            return;
+        };
+        let Some((x, z)) = calibrator.add(x, z) else {
+            return;
+        };
+
+        self.calibration = None;
+        if self.tilt.calibrate(x, z) {
+            log::info!("Calibrated the neutral position to ({}, {}).", x, z);
+            self.listener.signal_event(Event::Calibrated { x, z });
+        } else {
+            log::warn!("Rejecting implausible neutral position ({}, {}).", x, z);
+            self.listener.signal_event(Event::CalibrationFailed);
+        }
+    }
+
+    fn seed(&self) -> Option<u32> {
+        self.s.as_ref().and_then(|state| state.producer().seed())
+    }
+
+    fn restart_recording(&self) {
+        // A replay feeds the recording's entries, so they must not get discarded.
+        if self.replay.is_some() {
+            return;
+        }
+        if let Some(recording) = self.recording {
+            let seed = self.seed();
+            recording.update(|recording| recording.restart(seed));
+        }
+    }
+
+    fn record(&self, step: Step) {
+        // A replay takes the recorded steps once more, which must not get recorded twice.
+        if self.replay.is_some() {
+            return;
+        }
+        if let Some(recording) = self.recording {
+            recording.update(|recording| recording.record(step));
+        }
+    }
+
+    fn publish(&self) {
+        let seed = self.seed();
+        let next = self.next_tile();
+        let phase = self.s.as_ref().map_or(Phase::default(), State::phase);
+        self.status.update(|status| {
+            status.phase = phase;
+            status.seed = seed;
+            status.next = next;
+            status.score = self.score;
+            status.games_lost = self.games_lost;
+            status.paused = self.paused;
+            status.calibrating = self.calibration.is_some();
+            status.controls = self.controls;
+        });
+        if let Some(clock) = self.clock {
+            clock.set_running(!self.paused && !self.is_over());
+        }
     }
 }
 
 impl<'a, O, P> GameDriver<'a, O, P>
 where
-    O: Observer + Debug,
+    O: Observer + Listener + Clone + Debug,
     P: TileProducer,
 {
     /// Note: the contained peripherals start generating events right away, so be sure to
     /// set up the event handling as fast as possible
-    pub fn new(mailbox: Receiver<'a, Message, MAILBOX_CAPACITY>, o: O, mut producer: P) -> Self {
+    ///
+    /// Besides observing the board, `o` gets signalled the driver's [`Event`]s.
+    /// The game's progress is published to `status` after each processed message.
+    pub fn new(
+        mailbox: Receiver<'a, Message, MAILBOX_CAPACITY>,
+        o: O,
+        mut producer: P,
+        status: &'a SharedStatus,
+    ) -> Self {
         // initialize the game
         let mut game = Game::default()
             .place_tile(producer.generate_tile())
             .expect_left("the first tile should not end the game");
-        game.set_observer(o)
-            .expect("newly initialized game should not have observer set");
+        game.set_observer(Tracker {
+            observer: o.clone(),
+            status,
+        })
+        .expect("newly initialized game should not have observer set");
 
-        Self {
+        let mut score = Score::new();
+        score.add_tile();
+
+        let driver = Self {
             s: Some(State::TileFloating(game, producer)),
+            paused: false,
+            restored: false,
+            grace: Grace::Ticks(0),
+            score,
+            games_lost: 0,
+            listener: o,
+            status,
+            recording: None,
+            clock: None,
+            high_scores: None,
+            snapshots: None,
+            journal: None,
+            tilt: Tilt::default(),
+            tilt_column: None,
+            steered_by_moves: false,
+            calibration: None,
+            replay: None,
+            controls: 0,
             mailbox,
+        };
+        driver.publish();
+        driver
+    }
+
+    /// Records the input of every game into `recording`, so that it can be replayed later on
+    /// (see [`Message::Replay`]).
+    #[must_use]
+    pub fn with_recording(mut self, recording: &'a SharedRecording) -> Self {
+        self.recording = Some(recording);
+        self.restart_recording();
+        self
+    }
+
+    /// Keeps track of the time spent playing, see [`PlayClock`]. The clock is started right away,
+    /// as the first game is running already.
+    #[must_use]
+    pub fn with_clock(mut self, clock: &'a PlayClock) -> Self {
+        clock.restart();
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Enters every game that ends into `high_scores`, see [`Event::GameOver`]. Uses the clock
+    /// for the games' durations, if any (see [`with_clock`](Self::with_clock)).
+    #[must_use]
+    pub fn with_high_scores(mut self, high_scores: &'a SharedHighScores) -> Self {
+        self.high_scores = Some(high_scores);
+        self
+    }
+
+    /// Takes [`Snapshot`]s of the game into `snapshots` whenever the game is paused and every few
+    /// tiles, so that the game can be resumed after a reset (see [`Event::SnapshotTaken`]). Should
+    /// `snapshots` hold a snapshot already, its game is resumed once the driver runs.
+    #[must_use]
+    pub fn with_snapshots(mut self, snapshots: &'a SharedSnapshot) -> Self {
+        self.snapshots = Some(snapshots);
+        self.journal = Some(Journal::new());
+        self
+    }
+
+    /// Replaces the default conversion of accelerometer samples into columns.
+    #[must_use]
+    pub fn with_tilt(mut self, tilt: Tilt) -> Self {
+        self.tilt = tilt;
+        self
+    }
+
+    /// Advances the game according to `msg`, which is neither a pause nor a replay message.
+    fn play(&mut self, msg: Message) {
+        match msg {
+            Message::TimerTick => self.take_step(Step::Tick),
+            Message::BtnBPress => {
+                if self.is_over() {
+                    if !self.grace.is_over(self.clock) {
+                        log::debug!("Ignoring restart during grace period.");
+                    } else if let Some(transition) = self.map_state_with(State::restart) {
+                        self.process(transition);
+                    }
+                } else {
+                    self.take_step(Step::Rotate);
+                }
+            }
+            Message::AccelerometerData { x, z } => {
+                let column = self.tilt.column(x, z);
+                if self.tilt_column.replace(column) != Some(column) {
+                    self.steered_by_moves = false;
+                }
+                if !self.steered_by_moves {
+                    self.take_step(Step::MoveTo(column));
+                }
+            }
+            Message::MoveLeft | Message::MoveRight => {
+                self.steered_by_moves = true;
+                let from = self.tile_column();
+                self.map_state(|s| s.shift(msg == Message::MoveLeft));
+                self.track_move(from);
+            }
+            Message::Pause
+            | Message::Resume
+            | Message::TogglePause
+            | Message::Replay
+            | Message::Calibrate
+            | Message::Reset
+            | Message::SetSpeed(_)
+            | Message::SetTilt(_) => {
+                log::debug!("Ignoring control message.");
+            }
+        }
+    }
+
+    /// Takes `step`, be it derived from the input or replayed from the recording.
+    fn take_step(&mut self, step: Step) {
+        match step {
+            Step::Tick => {
+                // once the game is over, the ticks merely run down the grace period
+                let over = self.is_over();
+                if over {
+                    self.grace.tick();
+                }
+                let transition = self.map_state_with(State::tick);
+                if !over {
+                    self.track(Step::Tick);
+                }
+                if let Some(transition) = transition {
+                    self.process(transition);
+                }
+            }
+            Step::Rotate => {
+                self.map_state(State::rotate);
+                self.track(Step::Rotate);
+            }
+            Step::MoveTo(column) => {
+                let from = self.tile_column();
+                self.map_state(|s| s.move_to(column));
+                self.track_move(from);
+            }
         }
     }
 
+    fn set_speed(&mut self, speed: Speed) {
+        log::info!("Changing the speed.");
+        self.status.update(|status| status.speed = speed);
+        self.listener.signal_event(Event::SpeedChanged(speed));
+    }
+
+    fn reset(&mut self) {
+        log::info!("Abandoning the current game for a new one.");
+        // The new game starts right away, no matter whether the previous one was paused, being
+        // replayed or the tilt being calibrated.
+        if self.calibration.take().is_some() {
+            log::info!("Cancelling the calibration, the neutral position stays as it was.");
+        }
+        self.paused = false;
+        self.restored = false;
+        self.replay = None;
+        let transition = self.map_state_with(|s| s.reset(None));
+        self.process(transition);
+    }
+
+    fn start_replay(&mut self) {
+        let Some(recording) = self.recording else {
+            log::debug!("Ignoring replay, there is no recording.");
+            return;
+        };
+        let (complete, seed) =
+            recording.read(|recording| (recording.is_complete(), recording.seed()));
+        if !complete {
+            log::warn!("Ignoring replay, the recording's beginning has been overwritten.");
+            return;
+        }
+
+        log::info!("Replaying the recorded game.");
+        // Like a reset, the replay starts right away, no matter whether the game was paused.
+        self.paused = false;
+        self.restored = false;
+        self.replay = Some(Replay::default());
+        let transition = self.map_state_with(|s| s.reset(seed));
+        self.process(transition);
+    }
+
+    /// Takes the steps recorded before the next tick, then the tick itself. This way, the replay
+    /// runs at the same pace as the recorded game.
+    fn replay_tick(&mut self) {
+        let Some(recording) = self.recording else {
+            unreachable!("replay should require a recording");
+        };
+        let Some(mut replay) = self.replay else {
+            return;
+        };
+        while let Some(entry) = recording
+            .read(|recording| recording.get(replay.entry))
+            .filter(|entry| entry.tick <= replay.ticks)
+        {
+            replay.entry += 1;
+            self.replay = Some(replay);
+            self.take_step(entry.step);
+        }
+        if replay.ticks >= recording.read(Recording::ticks) {
+            log::info!("Replay finished.");
+            self.replay = None;
+            return;
+        }
+        replay.ticks += 1;
+        self.replay = Some(replay);
+        self.take_step(Step::Tick);
+    }
+
     pub async fn run(&mut self) -> Result<(), DriverError> {
+        // Resuming replays the journal, which is better done once the observer is up and running
+        // than while setting up the driver.
+        self.resume_snapshot();
+        self.publish();
+
         loop {
             let msg = self.mailbox.recv().await.map_err(|e| match e {
                 ReceiveError::Empty => unreachable!(),
                 ReceiveError::NoSender => DriverError::SenderDropped,
             })?;
 
-            defmt::trace!("Received message, processing it now.");
+            log::trace!("Received message, processing it now.");
 
             if !self.mailbox.is_empty() {
-                defmt::debug!("Additional messages are pending.");
+                log::debug!("Additional messages are pending.");
+            }
+            if msg.is_control() {
+                self.controls = self.controls.wrapping_add(1);
             }
 
             match msg {
-                Message::TimerTick => {
-                    self.map_state(State::tick);
+                // the speed is up to whoever generates the ticks
+                Message::SetSpeed(speed) => self.set_speed(speed),
+                Message::SetTilt(settings) => self.tilt.configure(settings),
+                Message::AccelerometerData { x, z } if self.calibration.is_some() => {
+                    self.calibrate(x, z);
                 }
-                Message::BtnBPress => {
-                    self.map_state(State::rotate);
+                // starting over is the way out of a calibration gone wrong
+                Message::Reset => self.reset(),
+                _ if self.calibration.is_some() => {
+                    log::debug!("Ignoring message, calibration is in progress.");
                 }
-                Message::AccelerometerData { x, z } => {
-                    let column = Self::convert_accel_to_column(x, z);
-                    self.map_state(|s| s.move_to(column));
+                Message::Calibrate => self.start_calibration(),
+                Message::Pause => self.pause(),
+                Message::Resume => self.resume(),
+                Message::TogglePause => self.toggle_pause(),
+                // button B alone declines the restored game, there may be no terminal to reset it
+                Message::BtnBPress if self.restored => self.reset(),
+                Message::Replay => self.start_replay(),
+                _ if self.paused => {
+                    log::debug!("Ignoring message, game is paused.");
+                }
+                Message::TimerTick if self.replay.is_some() => self.replay_tick(),
+                _ if self.replay.is_some() => {
+                    log::debug!("Ignoring message, game is being replayed.");
                 }
+                _ => self.play(msg),
             }
+
+            self.publish();
         }
     }
 }
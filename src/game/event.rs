@@ -0,0 +1,65 @@
+use super::{board::Board, score::Score, speed::Speed, tile::TileKind};
+
+/// Events signalled by [`GameDriver`](super::driver::GameDriver).
+///
+/// Changes to the board itself are reported through the engine's
+/// [`Observer`](microtile_engine::gameplay::game::Observer), these events cover everything the
+/// engine does not know about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    Paused,
+    Resumed,
+    /// The game has advanced to the given level, or has been reset to level 0 on game over.
+    LevelChanged(u8),
+    /// The game is over. The next game does not start before the player asks for it.
+    GameOver {
+        score: Score,
+        /// The game's place in the high scores, 0 being the best, if it made it there. Only
+        /// known if the driver keeps high scores at all, see
+        /// [`GameDriver::with_high_scores`](super::driver::GameDriver::with_high_scores).
+        place: Option<usize>,
+    },
+    /// A new game has started, which is never paused (without signalling
+    /// [`Resumed`](Self::Resumed) separately).
+    GameStarted,
+    /// The floating tile has landed, `tile` holding its cells and `board` all the settled cells
+    /// including them. The full rows of `board` are going to be cleared over the next ticks.
+    TileLanded {
+        tile: Board,
+        board: Board,
+    },
+    /// A tile has just been placed, the given one is going to follow it. Only signalled if the
+    /// upcoming tile is known in advance.
+    NextTile(TileKind),
+    /// The game has been paused (without signalling [`Paused`](Self::Paused) separately) to
+    /// calibrate the tilt, the board should be held in the neutral position now. Once done, the
+    /// game stays paused.
+    CalibrationStarted,
+    /// The tilt has been calibrated to the given (averaged) neutral accelerometer sample.
+    Calibrated {
+        x: i16,
+        z: i16,
+    },
+    /// The board has been held too far off level for the calibration to succeed.
+    CalibrationFailed,
+    /// A game saved before the last reset has been resumed, see
+    /// [`GameDriver::with_snapshots`](super::driver::GameDriver::with_snapshots). It is paused
+    /// (without signalling [`Paused`](Self::Paused) separately), waiting for the player to resume
+    /// it or to start over, be it by a reset or by pressing button B.
+    Restored(Score),
+    /// The game's [`Snapshot`](super::snapshot::Snapshot) has changed and is worth saving, e.g.
+    /// because the game has been paused or is over.
+    SnapshotTaken,
+    /// The game has outgrown the [`Journal`](super::snapshot::Journal), no more snapshots are
+    /// taken of it. The latest one has been dropped (signalling
+    /// [`SnapshotTaken`](Self::SnapshotTaken) separately), so the game cannot be resumed after a
+    /// reset. Taking snapshots starts over with the next game.
+    SnapshotsStopped,
+    /// The game's speed has been changed. Generating the ticks accordingly (at the current level)
+    /// is up to the listener.
+    SpeedChanged(Speed),
+}
+
+pub trait Listener {
+    fn signal_event(&self, event: Event);
+}
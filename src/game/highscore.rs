@@ -0,0 +1,189 @@
+use super::score::Score;
+use crate::settings::store::Record;
+use core::{
+    cell::RefCell,
+    fmt::{Debug, Formatter, Result as FmtResult},
+};
+use critical_section::Mutex;
+use heapless::Vec;
+
+/// A game that made it into the [`HighScores`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Entry {
+    pub points: u32,
+    pub rows: u32,
+    /// Time spent playing, see [`PlayClock`](super::clock::PlayClock).
+    pub duration_secs: u32,
+    /// Seed of the game's tile sequence, if the tiles were random.
+    pub seed: Option<u32>,
+}
+
+impl Entry {
+    const ENCODED_SIZE: usize = 16;
+
+    #[must_use]
+    pub fn new(score: &Score, duration_secs: u32, seed: Option<u32>) -> Self {
+        Self {
+            points: score.points,
+            rows: score.rows,
+            duration_secs,
+            seed,
+        }
+    }
+}
+
+/// The best games played so far, best first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HighScores {
+    entries: Vec<Entry, { HighScores::CAPACITY }>,
+}
+
+impl HighScores {
+    pub const CAPACITY: usize = 5;
+
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// The position `points` would take, 0 being the best, if any. Ties rank below the games
+    /// that scored as much before. Games without any points never rank.
+    #[must_use]
+    pub fn place(&self, points: u32) -> Option<usize> {
+        if points == 0 {
+            return None;
+        }
+        let place = self
+            .entries
+            .iter()
+            .position(|entry| entry.points < points)
+            .unwrap_or(self.entries.len());
+        (place < Self::CAPACITY).then_some(place)
+    }
+
+    /// Inserts `entry` at its [`place`](Self::place), dropping the worst entry if full. Returns
+    /// the place, if any.
+    pub fn insert(&mut self, entry: Entry) -> Option<usize> {
+        let place = self.place(entry.points)?;
+        if self.entries.is_full() {
+            self.entries.pop();
+        }
+        self.entries
+            .insert(place, entry)
+            .expect("there should be room after dropping the worst entry");
+        Some(place)
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// Encoded as the number of entries, a bit mask of the entries having a seed and two reserved
+/// bytes, followed by [`CAPACITY`](HighScores::CAPACITY) entries of points, rows, duration and
+/// seed (all `u32`, little endian). Tables holding another number of entries decode as well,
+/// keeping the best ones, so that the capacity may change without losing the table.
+impl Record for HighScores {
+    const VERSION: u16 = 1;
+    const ENCODED_SIZE: usize = 4 + Self::CAPACITY * Entry::ENCODED_SIZE;
+
+    fn encode(&self, bytes: &mut [u8]) {
+        let (header, slots) = bytes.split_at_mut(4);
+        #[allow(clippy::cast_possible_truncation)]
+        let count = self.entries.len() as u8;
+        header[0] = count;
+        for (index, (entry, slot)) in self
+            .entries
+            .iter()
+            .zip(slots.chunks_exact_mut(Entry::ENCODED_SIZE))
+            .enumerate()
+        {
+            slot[0..4].copy_from_slice(&entry.points.to_le_bytes());
+            slot[4..8].copy_from_slice(&entry.rows.to_le_bytes());
+            slot[8..12].copy_from_slice(&entry.duration_secs.to_le_bytes());
+            if let Some(seed) = entry.seed {
+                header[1] |= 1 << index;
+                slot[12..16].copy_from_slice(&seed.to_le_bytes());
+            }
+        }
+    }
+
+    fn decode(version: u16, bytes: &[u8]) -> Option<Self> {
+        if !(1..=Self::VERSION).contains(&version) || bytes.len() < 4 {
+            return None;
+        }
+        let (header, slots) = bytes.split_at(4);
+        let count = usize::from(header[0]);
+        let seeds = header[1];
+        if slots.len() % Entry::ENCODED_SIZE != 0 || count > slots.len() / Entry::ENCODED_SIZE {
+            return None;
+        }
+
+        let word = |slot: &[u8], i: usize| {
+            u32::from_le_bytes([slot[i], slot[i + 1], slot[i + 2], slot[i + 3]])
+        };
+        let mut scores = Self::new();
+        for (index, slot) in slots
+            .chunks_exact(Entry::ENCODED_SIZE)
+            .take(count.min(Self::CAPACITY))
+            .enumerate()
+        {
+            let entry = Entry {
+                points: word(slot, 0),
+                rows: word(slot, 4),
+                duration_secs: word(slot, 8),
+                seed: (seeds & (1 << index) != 0).then(|| word(slot, 12)),
+            };
+            // The entries have to be in order, otherwise the data is corrupted.
+            if scores.place(entry.points) != Some(index) {
+                return None;
+            }
+            scores.entries.push(entry).ok()?;
+        }
+        Some(scores)
+    }
+}
+
+/// [`HighScores`] shared between [`GameDriver`](super::driver::GameDriver) (entering the games)
+/// and any number of readers, e.g. the CLI.
+pub struct SharedHighScores(Mutex<RefCell<HighScores>>);
+
+impl SharedHighScores {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(Mutex::new(RefCell::new(HighScores::new())))
+    }
+
+    pub fn read<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&HighScores) -> R,
+    {
+        critical_section::with(|cs| f(&self.0.borrow_ref(cs)))
+    }
+
+    pub fn update<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut HighScores) -> R,
+    {
+        critical_section::with(|cs| f(&mut self.0.borrow_ref_mut(cs)))
+    }
+}
+
+impl Default for SharedHighScores {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for SharedHighScores {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        self.read(|scores| f.debug_tuple("SharedHighScores").field(scores).finish())
+    }
+}
@@ -1,12 +1,49 @@
+use super::{speed::Speed, tilt::TiltSettings};
+
 #[must_use]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Message {
     TimerTick,
     BtnBPress,
-    AccelerometerData { x: i16, z: i16 },
+    AccelerometerData {
+        x: i16,
+        z: i16,
+    },
+    /// Move the floating tile one column to the left, e.g. from the keyboard. Such moves take
+    /// precedence over the tilt until the board is tilted towards another column.
+    MoveLeft,
+    /// See [`MoveLeft`](Self::MoveLeft).
+    MoveRight,
+    Pause,
+    Resume,
+    TogglePause,
+    /// Restart the game right away, paused or not, and replay its
+    /// [`Recording`](super::record::Recording), see
+    /// [`GameDriver::with_recording`](super::driver::GameDriver::with_recording).
+    Replay,
+    /// Pause the game and find the neutral position from the following accelerometer samples.
+    Calibrate,
+    /// Abandon the current game and start a new one right away.
+    Reset,
+    /// Change the game's pace, see [`Event::SpeedChanged`](super::event::Event::SpeedChanged).
+    SetSpeed(Speed),
+    /// Change how the accelerometer samples are converted into columns, keeping the calibration.
+    SetTilt(TiltSettings),
 }
 
 impl Message {
     pub fn acceleration(x: i16, z: i16) -> Self {
         Self::AccelerometerData { x, z }
     }
+
+    /// Whether the message controls the game rather than playing it, i.e. is counted in
+    /// [`Status::controls`](super::status::Status::controls). Toggling the pause is left out, as it
+    /// stems from the buttons rather than from whoever waits for the count to change.
+    #[must_use]
+    pub fn is_control(self) -> bool {
+        matches!(
+            self,
+            Self::Pause | Self::Resume | Self::Replay | Self::Calibrate | Self::Reset
+        )
+    }
 }
@@ -1,3 +1,15 @@
+pub mod animation;
+pub mod board;
+pub mod clock;
 pub mod driver;
+pub mod event;
+pub mod highscore;
 pub mod message;
+pub mod record;
+pub mod score;
+pub mod shading;
+pub mod snapshot;
+pub mod speed;
+pub mod status;
 pub mod tile;
+pub mod tilt;
@@ -0,0 +1,220 @@
+use super::snapshot::Step;
+use core::{
+    cell::RefCell,
+    fmt::{Debug, Display, Formatter, Result as FmtResult},
+};
+use critical_section::Mutex;
+
+/// A [`Step`] taken by [`GameDriver`](super::driver::GameDriver) other than a tick, stamped with
+/// the number of ticks the game had processed before.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entry {
+    pub tick: u32,
+    pub step: Step,
+}
+
+impl Display for Entry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self.step {
+            Step::Tick => write!(f, "{} tick", self.tick),
+            Step::Rotate => write!(f, "{} rotate", self.tick),
+            Step::MoveTo(column) => write!(f, "{} column {}", self.tick, column),
+        }
+    }
+}
+
+/// Reasons for a [`Recording`] to refuse an imported [`Entry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportError {
+    /// The recording is full, importing the entry would overwrite the oldest one.
+    Full,
+    /// The entry is a tick, or stamped before the previous entry or after the recorded ticks.
+    OutOfOrder,
+}
+
+/// Input of a single game, i.e. everything it takes to reproduce the game (together with the
+/// tile producer's seed).
+///
+/// The input is recorded as the [`Step`]s it made the game take, i.e. the tilt is recorded as the
+/// columns it has moved the tile to. This way, a recording neither depends on the tilt settings
+/// nor on the calibration, and it takes only a few entries per tile. Ticks are merely counted,
+/// the entries are stamped with them.
+///
+/// Steps ignored while the game is paused as well as the pause handling itself are not recorded.
+/// The entries are kept in a ring buffer, so once [`CAPACITY`](Self::CAPACITY) entries have been
+/// recorded, the oldest ones get overwritten.
+pub struct Recording {
+    seed: Option<u32>,
+    ticks: u32,
+    // number of entries recorded since the game started, including overwritten ones
+    total: usize,
+    // set if the game has been resumed from a snapshot, i.e. its beginning is missing
+    resumed: bool,
+    entries: [Entry; Recording::CAPACITY],
+}
+
+impl Recording {
+    /// Number of entries kept, enough for a game of a few hundred tiles.
+    pub const CAPACITY: usize = 1024;
+
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            seed: None,
+            ticks: 0,
+            total: 0,
+            resumed: false,
+            entries: [Entry {
+                tick: 0,
+                step: Step::Tick,
+            }; Self::CAPACITY],
+        }
+    }
+
+    /// Discards all entries to record a new game, whose tiles have been seeded with `seed`.
+    pub fn restart(&mut self, seed: Option<u32>) {
+        self.seed = seed;
+        self.ticks = 0;
+        self.total = 0;
+        self.resumed = false;
+    }
+
+    /// Discards all entries to record the rest of a game resumed from a
+    /// [`Snapshot`](super::snapshot::Snapshot). Lacking the game's beginning, the recording is
+    /// never complete.
+    pub fn resume(&mut self, seed: Option<u32>) {
+        self.restart(seed);
+        self.resumed = true;
+    }
+
+    pub fn record(&mut self, step: Step) {
+        if step == Step::Tick {
+            self.ticks = self.ticks.saturating_add(1);
+            return;
+        }
+        self.push(Entry {
+            tick: self.ticks,
+            step,
+        });
+    }
+
+    /// Sets the number of ticks of a recording being imported, see [`import`](Self::import).
+    pub fn set_ticks(&mut self, ticks: u32) {
+        self.ticks = ticks;
+    }
+
+    /// Adds `entry` to a recording being imported, e.g. one printed on another device. Unlike
+    /// recorded entries, imported ones are expected to be stamped within the
+    /// [`ticks`](Self::ticks) set before.
+    pub fn import(&mut self, entry: Entry) -> Result<(), ImportError> {
+        let previous = self.total.checked_sub(1).and_then(|index| self.get(index));
+        if entry.step == Step::Tick
+            || entry.tick > self.ticks
+            || previous.is_some_and(|previous| entry.tick < previous.tick)
+        {
+            return Err(ImportError::OutOfOrder);
+        }
+        if self.total >= Self::CAPACITY {
+            return Err(ImportError::Full);
+        }
+        self.push(entry);
+        Ok(())
+    }
+
+    fn push(&mut self, entry: Entry) {
+        self.entries[self.total % Self::CAPACITY] = entry;
+        self.total += 1;
+    }
+
+    #[must_use]
+    pub fn seed(&self) -> Option<u32> {
+        self.seed
+    }
+
+    /// Number of ticks recorded so far.
+    #[must_use]
+    pub fn ticks(&self) -> u32 {
+        self.ticks
+    }
+
+    /// Index of the oldest entry still available.
+    #[must_use]
+    pub fn first(&self) -> usize {
+        self.total.saturating_sub(Self::CAPACITY)
+    }
+
+    /// Index following the latest entry, i.e. the number of entries recorded so far.
+    #[must_use]
+    pub fn end(&self) -> usize {
+        self.total
+    }
+
+    /// Whether the game has been recorded from its start and no entry has been overwritten yet,
+    /// i.e. whether the recording is replayable.
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        !self.resumed && self.first() == 0
+    }
+
+    /// The entry recorded as `index`th one, unless it has been overwritten already.
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<Entry> {
+        (self.first()..self.end())
+            .contains(&index)
+            .then(|| self.entries[index % Self::CAPACITY])
+    }
+
+    /// The available entries, oldest first.
+    pub fn entries(&self) -> impl Iterator<Item = Entry> + '_ {
+        (self.first()..self.end()).map(|index| self.entries[index % Self::CAPACITY])
+    }
+}
+
+impl Default for Recording {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// [`Recording`] shared between [`GameDriver`](super::driver::GameDriver) (the writer) and any
+/// number of readers, e.g. the CLI.
+pub struct SharedRecording(Mutex<RefCell<Recording>>);
+
+impl SharedRecording {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(Mutex::new(RefCell::new(Recording::new())))
+    }
+
+    pub fn read<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&Recording) -> R,
+    {
+        critical_section::with(|cs| f(&self.0.borrow_ref(cs)))
+    }
+
+    pub fn update<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut Recording) -> R,
+    {
+        critical_section::with(|cs| f(&mut self.0.borrow_ref_mut(cs)))
+    }
+}
+
+impl Default for SharedRecording {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for SharedRecording {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        self.read(|recording| {
+            f.debug_struct("SharedRecording")
+                .field("seed", &recording.seed)
+                .field("ticks", &recording.ticks)
+                .field("total", &recording.total)
+                .finish_non_exhaustive()
+        })
+    }
+}
@@ -0,0 +1,50 @@
+use core::cmp::min;
+
+/// Score of a single game.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Score {
+    pub points: u32,
+    pub rows: u32,
+    pub level: u8,
+    pub tiles: u32,
+}
+
+impl Score {
+    /// Points awarded for clearing one, two, three, ... rows at once. Multiplied by `level + 1`.
+    const ROW_POINTS: [u32; 4] = [40, 100, 300, 1200];
+    /// Number of cleared rows it takes to advance a level.
+    pub const ROWS_PER_LEVEL: u32 = 5;
+    pub const MAX_LEVEL: u8 = 9;
+
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            points: 0,
+            rows: 0,
+            level: 0,
+            tiles: 0,
+        }
+    }
+
+    pub fn add_tile(&mut self) {
+        self.tiles = self.tiles.saturating_add(1);
+    }
+
+    /// Accounts for `rows` rows cleared by a single tile, returning whether the level changed.
+    pub fn add_rows(&mut self, rows: u32) -> bool {
+        let Some(index) = rows.checked_sub(1) else {
+            return false;
+        };
+        let index = min(index as usize, Self::ROW_POINTS.len() - 1);
+        let points = Self::ROW_POINTS[index].saturating_mul(u32::from(self.level) + 1);
+
+        self.points = self.points.saturating_add(points);
+        self.rows = self.rows.saturating_add(rows);
+
+        let level = u8::try_from(self.rows / Self::ROWS_PER_LEVEL)
+            .map_or(Self::MAX_LEVEL, |level| min(level, Self::MAX_LEVEL));
+        let changed = level != self.level;
+        self.level = level;
+        changed
+    }
+}
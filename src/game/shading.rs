@@ -0,0 +1,113 @@
+//! How the LED matrix tells the falling tile apart from the settled cells.
+//!
+//! The shading is hardware-independent, so that it can be tested on the host. Rendering it onto
+//! the micro:bit's display lives in `device::display`.
+
+use super::board::Board;
+use core::ops::RangeInclusive;
+
+/// Whether the falling tile blinks, is dimmed, or both.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayStyle {
+    /// The falling tile is shown at the settled cells' brightness every other frame.
+    Blink,
+    /// The falling tile is shown at a brightness of its own, without blinking.
+    Dim,
+    /// The falling tile is shown at a brightness of its own every other frame.
+    Both,
+}
+
+impl DisplayStyle {
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            DisplayStyle::Blink => "blink",
+            DisplayStyle::Dim => "dim",
+            DisplayStyle::Both => "both",
+        }
+    }
+
+    #[must_use]
+    pub fn blinks(self) -> bool {
+        matches!(self, DisplayStyle::Blink | DisplayStyle::Both)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shading {
+    pub style: DisplayStyle,
+    /// Brightness of the settled cells.
+    pub settled: u8,
+    /// Brightness of the falling tile, unless it blinks at the settled cells' brightness.
+    pub falling: u8,
+}
+
+impl Shading {
+    pub const DEFAULT: Self = Self {
+        style: DisplayStyle::Dim,
+        settled: 9,
+        falling: 4,
+    };
+    /// The accepted values of [`settled`](Self::settled) and [`falling`](Self::falling), from
+    /// barely lit to the LEDs' maximum brightness.
+    pub const BRIGHTNESSES: RangeInclusive<u8> = 1..=9;
+
+    /// Brightness of the falling tile, 0 hiding it.
+    ///
+    /// Blinking styles show the tile in the `lit` frames only, the display alternating between
+    /// lit and unlit frames.
+    #[must_use]
+    pub fn falling_brightness(self, lit: bool) -> u8 {
+        match self.style {
+            DisplayStyle::Blink if lit => self.settled,
+            DisplayStyle::Dim => self.falling,
+            DisplayStyle::Both if lit => self.falling,
+            DisplayStyle::Blink | DisplayStyle::Both => 0,
+        }
+    }
+}
+
+impl Default for Shading {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// The falling tile (`active`) and the settled cells (`passive`) shaded for a single frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShadedBoard {
+    pub active: Board,
+    pub passive: Board,
+    settled: u8,
+    falling: u8,
+}
+
+impl ShadedBoard {
+    #[must_use]
+    pub fn new(active: Board, passive: Board, shading: Shading, lit: bool) -> Self {
+        Self {
+            active,
+            passive,
+            settled: shading.settled,
+            falling: shading.falling_brightness(lit),
+        }
+    }
+
+    /// Brightness of the settled cells.
+    #[must_use]
+    pub fn settled(&self) -> u8 {
+        self.settled
+    }
+
+    /// Row 0 is the bottom row, column 0 the leftmost one, see [`Board::is_set`].
+    #[must_use]
+    pub fn brightness(&self, row: usize, column: usize) -> u8 {
+        if self.passive.is_set(row, column) {
+            self.settled
+        } else if self.active.is_set(row, column) {
+            self.falling
+        } else {
+            0
+        }
+    }
+}
@@ -0,0 +1,277 @@
+//! Snapshots of the game in progress, so that it survives a reset or a power loss.
+//!
+//! The engine can only be driven from an empty board, there is no way to set up its state
+//! directly. Hence, a [`Snapshot`] holds the state of the game (the boards, the score and, by way
+//! of the seed and the number of tiles placed, the tile producer's state) along with a
+//! [`Journal`] of the steps taken since the game started. Resuming the game replays the journal
+//! and checks the outcome against the state.
+
+use super::{board::Board, score::Score};
+use crate::settings::store::Record;
+use core::{
+    cell::RefCell,
+    fmt::{Debug, Formatter, Result as FmtResult},
+};
+use critical_section::Mutex;
+
+/// What the game did in reaction to a single message, as far as the engine is concerned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    Tick,
+    /// The floating tile has been rotated.
+    Rotate,
+    /// The floating tile has been moved to the given column.
+    MoveTo(u8),
+}
+
+/// The [`Journal`] is out of space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JournalFull;
+
+/// The [`Step`]s of a game, packed into 4 bits each: `0..=7` stand for a run of one to eight ticks,
+/// `8..=12` for moving to column 0 to 4 and `13` for a rotation.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Journal {
+    // Unused nibbles are kept zero, so that journals holding the same steps are equal.
+    nibbles: [u8; Journal::BYTES],
+    len: usize,
+}
+
+impl Journal {
+    /// Number of bytes the steps are packed into, enough for about 200 tiles.
+    pub const BYTES: usize = 480;
+    pub const CAPACITY: usize = 2 * Self::BYTES;
+
+    const MAX_TICKS: u8 = 8;
+    const MOVE_TO: u8 = 8;
+    #[allow(clippy::cast_possible_truncation)]
+    const ROTATE: u8 = Self::MOVE_TO + Board::COLUMNS as u8;
+
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            nibbles: [0; Self::BYTES],
+            len: 0,
+        }
+    }
+
+    /// Number of nibbles in use.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    pub fn push(&mut self, step: Step) -> Result<(), JournalFull> {
+        let nibble = match step {
+            Step::Tick => match self.len.checked_sub(1).map(|last| self.nibble(last)) {
+                Some(run) if run < Self::MAX_TICKS - 1 => {
+                    self.set_nibble(self.len - 1, run + 1);
+                    return Ok(());
+                }
+                _ => 0,
+            },
+            Step::Rotate => Self::ROTATE,
+            Step::MoveTo(column) => {
+                debug_assert!(usize::from(column) < Board::COLUMNS);
+                Self::MOVE_TO + column
+            }
+        };
+        if self.len == Self::CAPACITY {
+            return Err(JournalFull);
+        }
+        self.set_nibble(self.len, nibble);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// The steps, oldest first.
+    pub fn steps(&self) -> impl Iterator<Item = Step> + '_ {
+        (0..self.len).flat_map(|index| {
+            let nibble = self.nibble(index);
+            let (step, count) = match nibble {
+                Self::ROTATE => (Step::Rotate, 1),
+                Self::MOVE_TO.. => (Step::MoveTo(nibble - Self::MOVE_TO), 1),
+                run => (Step::Tick, run + 1),
+            };
+            (0..count).map(move |_| step)
+        })
+    }
+
+    fn nibble(&self, index: usize) -> u8 {
+        (self.nibbles[index / 2] >> (4 * (index % 2))) & 0x0f
+    }
+
+    fn set_nibble(&mut self, index: usize, nibble: u8) {
+        let shift = 4 * (index % 2);
+        let byte = &mut self.nibbles[index / 2];
+        *byte = (*byte & !(0x0f << shift)) | (nibble << shift);
+    }
+
+    fn decode(len: usize, bytes: &[u8]) -> Option<Self> {
+        if len > Self::CAPACITY {
+            return None;
+        }
+        let mut journal = Self::new();
+        journal.nibbles.copy_from_slice(bytes);
+        journal.len = len;
+        let valid = (0..len).all(|index| journal.nibble(index) <= Self::ROTATE)
+            && (len..Self::CAPACITY).all(|index| journal.nibble(index) == 0);
+        valid.then_some(journal)
+    }
+}
+
+impl Default for Journal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for Journal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("Journal")
+            .field("len", &self.len)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A game in progress, see the [module docs](self).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    /// Seed of the game's tile sequence.
+    pub seed: u32,
+    pub score: Score,
+    /// Time spent playing, see [`PlayClock`](super::clock::PlayClock).
+    pub play_secs: u32,
+    /// The floating tile, if any.
+    pub active: Board,
+    pub passive: Board,
+    pub journal: Journal,
+}
+
+impl Snapshot {
+    const HEADER_SIZE: usize = 32;
+}
+
+/// Encoded as (all integers little endian)
+///
+/// | bytes | content                                   |
+/// |-------|-------------------------------------------|
+/// | 1     | 1 if there is a snapshot at all, else 0   |
+/// | 1     | level                                     |
+/// | 2     | length of the journal in nibbles          |
+/// | 4     | seed                                      |
+/// | 4     | points                                    |
+/// | 4     | rows                                      |
+/// | 4     | tiles                                     |
+/// | 4     | time spent playing in seconds             |
+/// | 4     | active board, see [`Board::bits`]         |
+/// | 4     | passive board                             |
+/// | 480   | journal                                   |
+///
+/// Without a snapshot, all but the flag are zero.
+impl Record for Option<Snapshot> {
+    const VERSION: u16 = 1;
+    const ENCODED_SIZE: usize = Snapshot::HEADER_SIZE + Journal::BYTES;
+
+    fn encode(&self, bytes: &mut [u8]) {
+        let Some(snapshot) = self else {
+            return;
+        };
+        bytes[0] = 1;
+        bytes[1] = snapshot.score.level;
+        #[allow(clippy::cast_possible_truncation)]
+        let len = snapshot.journal.len as u16;
+        bytes[2..4].copy_from_slice(&len.to_le_bytes());
+        let words = [
+            snapshot.seed,
+            snapshot.score.points,
+            snapshot.score.rows,
+            snapshot.score.tiles,
+            snapshot.play_secs,
+            snapshot.active.bits(),
+            snapshot.passive.bits(),
+        ];
+        for (word, slot) in words.iter().zip(bytes[4..].chunks_exact_mut(4)) {
+            slot.copy_from_slice(&word.to_le_bytes());
+        }
+        bytes[Snapshot::HEADER_SIZE..].copy_from_slice(&snapshot.journal.nibbles);
+    }
+
+    fn decode(version: u16, bytes: &[u8]) -> Option<Self> {
+        if version != Self::VERSION || bytes.len() != Self::ENCODED_SIZE {
+            return None;
+        }
+        match bytes[0] {
+            0 => return Some(None),
+            1 => {}
+            _ => return None,
+        }
+
+        let level = bytes[1];
+        let len = usize::from(u16::from_le_bytes([bytes[2], bytes[3]]));
+        let word =
+            |i: usize| u32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]]);
+        if level > Score::MAX_LEVEL {
+            return None;
+        }
+        Some(Some(Snapshot {
+            seed: word(4),
+            score: Score {
+                points: word(8),
+                rows: word(12),
+                level,
+                tiles: word(16),
+            },
+            play_secs: word(20),
+            active: Board::from_bits(word(24))?,
+            passive: Board::from_bits(word(28))?,
+            journal: Journal::decode(len, &bytes[Snapshot::HEADER_SIZE..])?,
+        }))
+    }
+}
+
+/// The latest [`Snapshot`], if any, shared between [`GameDriver`](super::driver::GameDriver)
+/// (taking the snapshots) and whoever persists them.
+pub struct SharedSnapshot(Mutex<RefCell<Option<Snapshot>>>);
+
+impl SharedSnapshot {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(Mutex::new(RefCell::new(None)))
+    }
+
+    pub fn read<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&Option<Snapshot>) -> R,
+    {
+        critical_section::with(|cs| f(&self.0.borrow_ref(cs)))
+    }
+
+    pub fn update<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut Option<Snapshot>) -> R,
+    {
+        critical_section::with(|cs| f(&mut self.0.borrow_ref_mut(cs)))
+    }
+}
+
+impl Default for SharedSnapshot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for SharedSnapshot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        self.read(|snapshot| f.debug_tuple("SharedSnapshot").field(snapshot).finish())
+    }
+}
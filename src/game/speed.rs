@@ -0,0 +1,49 @@
+use core::ops::RangeInclusive;
+
+/// Pace of the game's ticks.
+///
+/// The tick timer runs `soft_drop` times faster than the game ticks, so that holding button A
+/// (soft dropping) ticks on every timer event, while otherwise only every `soft_drop`-th event
+/// ticks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Speed {
+    /// Milliseconds between two ticks at level 0, unless soft dropping.
+    pub period_ms: u16,
+    /// How many times faster the game ticks while soft dropping.
+    pub soft_drop: u8,
+}
+
+impl Speed {
+    pub const DEFAULT: Self = Self {
+        period_ms: 1000,
+        soft_drop: 3,
+    };
+    /// The accepted values of [`period_ms`](Self::period_ms).
+    pub const PERIODS: RangeInclusive<u16> = 100..=5000;
+    /// The accepted values of [`soft_drop`](Self::soft_drop).
+    pub const SOFT_DROPS: RangeInclusive<u8> = 1..=10;
+    /// Each level speeds up the ticks by this fraction of the level 0 rate.
+    const LEVEL_DIVISOR: u32 = 3;
+
+    /// Microseconds between two ticks at `level`, unless soft dropping.
+    #[must_use]
+    pub fn period_us(self, level: u8) -> u32 {
+        u32::from(self.period_ms) * 1000 * Self::LEVEL_DIVISOR
+            / (Self::LEVEL_DIVISOR + u32::from(level))
+    }
+
+    /// Microseconds between two ticks at `level` while soft dropping, i.e. the tick timer's
+    /// period.
+    ///
+    /// A `soft_drop` of 0 is treated like 1.
+    #[must_use]
+    pub fn soft_drop_period_us(self, level: u8) -> u32 {
+        self.period_us(level) / u32::from(self.soft_drop.max(1))
+    }
+}
+
+impl Default for Speed {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
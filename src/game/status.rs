@@ -0,0 +1,138 @@
+use super::{board::Board, score::Score, speed::Speed, tile::TileKind};
+use core::{
+    cell::Cell,
+    fmt::{Debug, Formatter, Result as FmtResult},
+    future::poll_fn,
+    task::Poll,
+};
+use critical_section::Mutex;
+use futures::task::AtomicWaker;
+
+/// Stage of the game, mirroring the driver's internal state machine.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Phase {
+    /// The next tile is about to be placed.
+    #[default]
+    TileNeeded,
+    /// A tile is falling in the given column.
+    TileFloating {
+        column: u8,
+    },
+    /// The tile has landed, full rows are being cleared.
+    ProcessRows,
+    GameOver,
+}
+
+impl Phase {
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::TileNeeded => "tile needed",
+            Phase::TileFloating { .. } => "tile floating",
+            Phase::ProcessRows => "processing rows",
+            Phase::GameOver => "game over",
+        }
+    }
+}
+
+/// The game's state as of the last processed message.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Status {
+    /// The floating tile, if any.
+    pub active: Board,
+    /// Everything that has settled already.
+    pub passive: Board,
+    pub phase: Phase,
+    pub score: Score,
+    pub games_lost: u32,
+    pub paused: bool,
+    /// Whether the tilt is being calibrated, which implies being paused.
+    pub calibrating: bool,
+    /// Number of control messages processed so far, whether they took effect or not, see
+    /// [`Message::is_control`](super::message::Message::is_control).
+    pub controls: u32,
+    /// The speed requested last, see [`Event::SpeedChanged`](super::event::Event::SpeedChanged).
+    pub speed: Speed,
+    /// Seed of the current game's tile sequence, if the tiles are random.
+    pub seed: Option<u32>,
+    /// The tile to be placed next, if known in advance.
+    pub next: Option<TileKind>,
+}
+
+impl Status {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            active: Board::empty(),
+            passive: Board::empty(),
+            phase: Phase::TileNeeded,
+            score: Score::new(),
+            games_lost: 0,
+            paused: false,
+            calibrating: false,
+            controls: 0,
+            speed: Speed::DEFAULT,
+            seed: None,
+            next: None,
+        }
+    }
+}
+
+/// [`Status`] shared between [`GameDriver`](super::driver::GameDriver) (the writer) and any
+/// number of readers, e.g. the CLI.
+///
+/// A single reader at a time may wait for the driver to process a control message, see
+/// [`control_processed`](Self::control_processed).
+pub struct SharedStatus(Mutex<Cell<Status>>, AtomicWaker);
+
+impl SharedStatus {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(Mutex::new(Cell::new(Status::new())), AtomicWaker::new())
+    }
+
+    #[must_use]
+    pub fn get(&self) -> Status {
+        critical_section::with(|cs| self.0.borrow(cs).get())
+    }
+
+    pub fn update<F>(&self, f: F)
+    where
+        F: FnOnce(&mut Status),
+    {
+        critical_section::with(|cs| {
+            let cell = self.0.borrow(cs);
+            let mut status = cell.get();
+            f(&mut status);
+            cell.set(status);
+        });
+        self.1.wake();
+    }
+
+    /// Waits until [`Status::controls`] differs from `controls`, i.e. until the driver has
+    /// processed the control message sent after reading `controls`, returning the status then.
+    pub async fn control_processed(&self, controls: u32) -> Status {
+        poll_fn(|cx| {
+            self.1.register(cx.waker());
+            let status = self.get();
+            if status.controls == controls {
+                Poll::Pending
+            } else {
+                Poll::Ready(status)
+            }
+        })
+        .await
+    }
+}
+
+impl Default for SharedStatus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for SharedStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_tuple("SharedStatus").field(&self.get()).finish()
+    }
+}
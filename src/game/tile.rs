@@ -1,8 +1,58 @@
 use core::mem::replace;
+use heapless::Deque;
 use microtile_engine::geometry::tile::BasicTile;
 
+/// Copyable stand-in for [`BasicTile`], e.g. to report upcoming tiles to the player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TileKind {
+    Square,
+    Line,
+    Diagonal,
+}
+
+impl TileKind {
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            TileKind::Square => "square",
+            TileKind::Line => "line",
+            TileKind::Diagonal => "diagonal",
+        }
+    }
+}
+
+impl From<&BasicTile> for TileKind {
+    fn from(tile: &BasicTile) -> Self {
+        match tile {
+            BasicTile::Square => TileKind::Square,
+            BasicTile::Line => TileKind::Line,
+            BasicTile::Diagonal => TileKind::Diagonal,
+        }
+    }
+}
+
 pub trait TileProducer {
     fn generate_tile(&mut self) -> BasicTile;
+
+    /// Called right before the first tile of a new game is generated.
+    fn start_game(&mut self) {}
+
+    /// Like [`start_game`](Self::start_game), but seeds the new game with `seed` to replay it.
+    /// Producers that are not seedable ignore the seed.
+    fn start_game_with_seed(&mut self, _seed: u32) {
+        self.start_game();
+    }
+
+    /// Seed reproducing the current game's sequence of tiles, if the producer is seedable.
+    fn seed(&self) -> Option<u32> {
+        None
+    }
+
+    /// The tile the next call to [`generate_tile`](Self::generate_tile) returns, if the producer
+    /// knows it in advance.
+    fn peek(&self) -> Option<&BasicTile> {
+        None
+    }
 }
 
 pub struct TileIterator<P> {
@@ -76,3 +126,177 @@ impl TileProducer for LoopingProducer {
         self.advance()
     }
 }
+
+/// Minimal xorshift pseudo-random number generator, good enough for shuffling tiles.
+struct XorShift32 {
+    state: u32,
+}
+
+impl XorShift32 {
+    fn new(seed: u32) -> Self {
+        // xorshift gets stuck on an all-zero state
+        let state = if seed == 0 { 0x9e37_79b9 } else { seed };
+        Self { state }
+    }
+
+    fn next(&mut self) -> u32 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.state = x;
+        x
+    }
+
+    /// Pseudo-random number in `0..bound`.
+    fn below(&mut self, bound: usize) -> usize {
+        // The bias introduced by the modulo is negligible for our tiny bounds.
+        #[allow(clippy::cast_possible_truncation)]
+        let x = self.next() as usize;
+        x % bound
+    }
+}
+
+/// Number of tiles in a [`RandomProducer`]'s bag.
+pub const BAG_SIZE: usize = 3;
+
+/// [`TileProducer`] drawing tiles from a shuffled bag.
+///
+/// The bag holds every [`BasicTile`] exactly once. Tiles are drawn from the bag until it is empty,
+/// then the bag is refilled and shuffled again. This way, the sequence is unpredictable while no
+/// tile is ever starved: there are at most `2 * BAG_SIZE - 2` other tiles between two tiles of the
+/// same kind.
+///
+/// The sequence is fully determined by the seed. Each new game (see
+/// [`start_game`](TileProducer::start_game)) derives a fresh seed, which is reported via
+/// [`seed`](TileProducer::seed) so that the game can be replayed.
+pub struct RandomProducer {
+    seed: u32,
+    rng: XorShift32,
+    bag: [BasicTile; BAG_SIZE],
+    remaining: usize,
+}
+
+impl RandomProducer {
+    #[must_use]
+    pub fn new(seed: u32) -> Self {
+        Self {
+            seed,
+            rng: XorShift32::new(seed),
+            bag: [BasicTile::Square, BasicTile::Line, BasicTile::Diagonal],
+            remaining: 0,
+        }
+    }
+
+    fn reseed(&mut self, seed: u32) {
+        *self = Self::new(seed);
+    }
+
+    fn refill(&mut self) {
+        // Fisher-Yates shuffle
+        for i in (1..BAG_SIZE).rev() {
+            let j = self.rng.below(i + 1);
+            self.bag.swap(i, j);
+        }
+        self.remaining = BAG_SIZE;
+    }
+}
+
+impl TileProducer for RandomProducer {
+    fn generate_tile(&mut self) -> BasicTile {
+        if self.remaining == 0 {
+            self.refill();
+        }
+        self.remaining -= 1;
+        self.bag[self.remaining].clone()
+    }
+
+    fn start_game(&mut self) {
+        let seed = self.rng.next();
+        self.reseed(seed);
+    }
+
+    fn start_game_with_seed(&mut self, seed: u32) {
+        self.reseed(seed);
+    }
+
+    fn seed(&self) -> Option<u32> {
+        Some(self.seed)
+    }
+}
+
+/// [`TileProducer`] buffering the next `DEPTH` tiles of another producer, so that they can be
+/// [`peek`](TileProducer::peek)ed at.
+///
+/// Starting a new game discards the buffered tiles, i.e. the new game's tiles are exactly the ones
+/// the wrapped producer generates for it.
+pub struct Lookahead<P, const DEPTH: usize> {
+    producer: P,
+    buffer: Deque<BasicTile, DEPTH>,
+}
+
+impl<P, const DEPTH: usize> Lookahead<P, DEPTH>
+where
+    P: TileProducer,
+{
+    /// # Panics
+    ///
+    /// Panics if `DEPTH` is zero.
+    #[must_use]
+    pub fn new(producer: P) -> Self {
+        assert!(DEPTH > 0, "lookahead needs to buffer at least one tile");
+        let mut lookahead = Self {
+            producer,
+            buffer: Deque::new(),
+        };
+        lookahead.fill();
+        lookahead
+    }
+
+    fn fill(&mut self) {
+        while !self.buffer.is_full() {
+            let tile = self.producer.generate_tile();
+            // cannot fail, the buffer is not full
+            let _ = self.buffer.push_back(tile);
+        }
+    }
+
+    /// The buffered tiles, the one to be generated next coming first.
+    pub fn upcoming(&self) -> impl Iterator<Item = &BasicTile> {
+        self.buffer.iter()
+    }
+}
+
+impl<P, const DEPTH: usize> TileProducer for Lookahead<P, DEPTH>
+where
+    P: TileProducer,
+{
+    fn generate_tile(&mut self) -> BasicTile {
+        let tile = self
+            .buffer
+            .pop_front()
+            .expect("lookahead buffer should never be empty");
+        self.fill();
+        tile
+    }
+
+    fn start_game(&mut self) {
+        self.producer.start_game();
+        self.buffer.clear();
+        self.fill();
+    }
+
+    fn start_game_with_seed(&mut self, seed: u32) {
+        self.producer.start_game_with_seed(seed);
+        self.buffer.clear();
+        self.fill();
+    }
+
+    fn seed(&self) -> Option<u32> {
+        self.producer.seed()
+    }
+
+    fn peek(&self) -> Option<&BasicTile> {
+        self.buffer.front()
+    }
+}
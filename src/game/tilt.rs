@@ -0,0 +1,325 @@
+use super::message::Message;
+use core::{
+    f32::consts::{FRAC_PI_2, FRAC_PI_8, PI},
+    ops::RangeInclusive,
+};
+use micromath::F32Ext;
+
+/// Exponential low-pass filter smoothing the raw accelerometer samples.
+///
+/// The filter works on the acceleration vector rather than on the derived angle, so that it does
+/// not have to care about the angle wrapping around.
+#[derive(Debug, Clone, Copy)]
+pub struct LowPass {
+    smoothing: f32,
+    state: Option<(f32, f32)>,
+}
+
+impl LowPass {
+    /// `smoothing` is the weight of the latest sample, `1.0` disables the filter altogether.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `smoothing` is not in `(0, 1]`.
+    #[must_use]
+    pub fn new(smoothing: f32) -> Self {
+        assert!(
+            smoothing > 0.0 && smoothing <= 1.0,
+            "smoothing should be in (0, 1]"
+        );
+        Self {
+            smoothing,
+            state: None,
+        }
+    }
+
+    pub fn filter(&mut self, x: f32, z: f32) -> (f32, f32) {
+        let filtered = match self.state {
+            Some((fx, fz)) => (
+                fx + self.smoothing * (x - fx),
+                fz + self.smoothing * (z - fz),
+            ),
+            // nothing to smooth with yet
+            None => (x, z),
+        };
+        self.state = Some(filtered);
+        filtered
+    }
+
+    pub fn reset(&mut self) {
+        self.state = None;
+    }
+}
+
+/// Maps the board's inclination onto a column.
+///
+/// Once a column has been selected, the selector sticks to it until the inclination leaves the
+/// column by more than `band`. This keeps noise around the thresholds between two columns from
+/// making the tile jitter.
+///
+/// By default, the center column is selected while the board is held level. Use
+/// [`set_neutral`](Self::set_neutral) to center the columns around a different inclination.
+#[derive(Debug, Clone, Copy)]
+pub struct ColumnSelector {
+    band: f32,
+    thresholds: [f32; 4],
+    column: Option<u8>,
+}
+
+impl ColumnSelector {
+    // Thresholds when measuring the angle from the vertical z-axis
+    const COLUMN_0_THRESHOLD_UNCOMP: f32 = -PI * 3.0 / 16.0;
+    const COLUMN_1_THRESHOLD_UNCOMP: f32 = -PI / 16.0;
+    const COLUMN_2_THRESHOLD_UNCOMP: f32 = -Self::COLUMN_1_THRESHOLD_UNCOMP;
+    const COLUMN_3_THRESHOLD_UNCOMP: f32 = -Self::COLUMN_0_THRESHOLD_UNCOMP;
+
+    // Thresholds when measuring the angle using the values coming from the
+    // accelerometer (that is from negative x-axis, or measured from the vertical
+    // z-axis with an offset of pi/2)
+    const COLUMN_0_THRESHOLD: f32 = Self::COLUMN_0_THRESHOLD_UNCOMP - FRAC_PI_2;
+    const COLUMN_1_THRESHOLD: f32 = Self::COLUMN_1_THRESHOLD_UNCOMP - FRAC_PI_2;
+    const COLUMN_2_THRESHOLD: f32 = Self::COLUMN_2_THRESHOLD_UNCOMP - FRAC_PI_2;
+    const COLUMN_3_THRESHOLD: f32 = Self::COLUMN_3_THRESHOLD_UNCOMP - FRAC_PI_2;
+
+    /// Widest sensible band, i.e. half a column's width.
+    pub const MAX_BAND: f32 = PI / 16.0;
+    /// Angle of the board being held level.
+    pub const LEVEL: f32 = -FRAC_PI_2;
+    /// Largest supported deviation of the neutral angle from [`LEVEL`](Self::LEVEL).
+    pub const MAX_NEUTRAL_OFFSET: f32 = PI / 4.0;
+    const LEVEL_THRESHOLDS: [f32; 4] = [
+        Self::COLUMN_0_THRESHOLD,
+        Self::COLUMN_1_THRESHOLD,
+        Self::COLUMN_2_THRESHOLD,
+        Self::COLUMN_3_THRESHOLD,
+    ];
+
+    /// # Panics
+    ///
+    /// Panics if `band` is not in `[0, MAX_BAND)`.
+    #[must_use]
+    pub fn new(band: f32) -> Self {
+        assert!(
+            (0.0..Self::MAX_BAND).contains(&band),
+            "band should be in [0, MAX_BAND)"
+        );
+        Self {
+            band,
+            thresholds: Self::LEVEL_THRESHOLDS,
+            column: None,
+        }
+    }
+
+    /// Centers the columns around `neutral` (as in `z.atan2(x)`), returning whether `neutral` is
+    /// within [`MAX_NEUTRAL_OFFSET`](Self::MAX_NEUTRAL_OFFSET) of [`LEVEL`](Self::LEVEL).
+    /// Otherwise, the thresholds are left untouched.
+    pub fn set_neutral(&mut self, neutral: f32) -> bool {
+        let offset = neutral - Self::LEVEL;
+        if !(-Self::MAX_NEUTRAL_OFFSET..=Self::MAX_NEUTRAL_OFFSET).contains(&offset) {
+            return false;
+        }
+        for (threshold, level) in self.thresholds.iter_mut().zip(Self::LEVEL_THRESHOLDS) {
+            *threshold = level + offset;
+        }
+        true
+    }
+
+    /// The column `angle` (as in `z.atan2(x)`) falls into, without any hysteresis.
+    #[must_use]
+    pub fn column_at(&self, angle: f32) -> u8 {
+        let [t0, t1, t2, t3] = self.thresholds;
+        if angle < t0 {
+            0
+        } else if angle < t1 {
+            1
+        } else if angle < t2 {
+            2
+        } else if angle < t3 {
+            3
+        } else {
+            4
+        }
+    }
+
+    pub fn select(&mut self, angle: f32) -> u8 {
+        let column = match self.column {
+            Some(current) => {
+                // Shifting the angle towards the current column by the band's width only changes
+                // the outcome, if the angle is within the band around a threshold.
+                if self.column_at(angle) > current {
+                    self.column_at(angle - self.band).max(current)
+                } else {
+                    self.column_at(angle + self.band).min(current)
+                }
+            }
+            None => self.column_at(angle),
+        };
+        self.column = Some(column);
+        column
+    }
+
+    pub fn reset(&mut self) {
+        self.column = None;
+    }
+
+    /// Replaces the band, see [`new`](Self::new).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `band` is not in `[0, MAX_BAND)`.
+    pub fn set_band(&mut self, band: f32) {
+        *self = Self {
+            thresholds: self.thresholds,
+            column: self.column,
+            ..Self::new(band)
+        };
+    }
+}
+
+/// Synthetic accelerometer sample, which an uncalibrated [`ColumnSelector`] maps onto `column`,
+/// e.g. to play without the accelerometer.
+pub fn tilt_towards(column: u8) -> Message {
+    // Columns are centered around the vertical, each one spanning an angle of pi/8.
+    let angle = ColumnSelector::LEVEL + (f32::from(column) - 2.0) * FRAC_PI_8;
+    #[allow(clippy::cast_possible_truncation)]
+    let (x, z) = ((1000.0 * angle.cos()) as i16, (1000.0 * angle.sin()) as i16);
+    Message::acceleration(x, z)
+}
+
+/// [`Tilt`]'s parameters in the integral units they are configured in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TiltSettings {
+    /// Weight of the latest sample in percent, see [`LowPass::new`].
+    pub smoothing: u8,
+    /// Band in steps of [`HYSTERESIS_STEP`](Self::HYSTERESIS_STEP), see [`ColumnSelector::new`].
+    pub hysteresis: u8,
+}
+
+impl TiltSettings {
+    pub const DEFAULT: Self = Self {
+        smoothing: 30,
+        hysteresis: 3,
+    };
+    /// The accepted values of [`smoothing`](Self::smoothing).
+    pub const SMOOTHINGS: RangeInclusive<u8> = 1..=100;
+    /// The accepted values of [`hysteresis`](Self::hysteresis).
+    pub const HYSTERESES: RangeInclusive<u8> = 0..=5;
+    pub const HYSTERESIS_STEP: f32 = ColumnSelector::MAX_BAND / 6.0;
+
+    /// The smoothing as expected by [`LowPass::new`], clamped to the accepted values.
+    #[must_use]
+    pub fn smoothing(self) -> f32 {
+        let percent = self
+            .smoothing
+            .clamp(*Self::SMOOTHINGS.start(), *Self::SMOOTHINGS.end());
+        f32::from(percent) / 100.0
+    }
+
+    /// The band as expected by [`ColumnSelector::new`], clamped to the accepted values.
+    #[must_use]
+    pub fn band(self) -> f32 {
+        f32::from(self.hysteresis.min(*Self::HYSTERESES.end())) * Self::HYSTERESIS_STEP
+    }
+}
+
+impl Default for TiltSettings {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Converts accelerometer samples into columns, see [`LowPass`] and [`ColumnSelector`].
+#[derive(Debug, Clone, Copy)]
+pub struct Tilt {
+    filter: LowPass,
+    selector: ColumnSelector,
+}
+
+impl Tilt {
+    pub const DEFAULT_SMOOTHING: f32 = 0.3;
+    pub const DEFAULT_BAND: f32 = PI / 32.0;
+
+    /// See [`LowPass::new`] and [`ColumnSelector::new`] for the parameters.
+    #[must_use]
+    pub fn new(smoothing: f32, band: f32) -> Self {
+        Self {
+            filter: LowPass::new(smoothing),
+            selector: ColumnSelector::new(band),
+        }
+    }
+
+    #[must_use]
+    pub fn with_settings(settings: TiltSettings) -> Self {
+        Self::new(settings.smoothing(), settings.band())
+    }
+
+    /// Replaces the smoothing and the band, keeping the calibration.
+    pub fn configure(&mut self, settings: TiltSettings) {
+        self.filter = LowPass::new(settings.smoothing());
+        self.selector.set_band(settings.band());
+    }
+
+    /// Centers the columns around the inclination of the (averaged) sample `(x, z)`, see
+    /// [`ColumnSelector::set_neutral`].
+    pub fn calibrate(&mut self, x: i16, z: i16) -> bool {
+        self.selector.set_neutral(f32::from(z).atan2(f32::from(x)))
+    }
+
+    pub fn column(&mut self, x: i16, z: i16) -> u8 {
+        let (x, z) = self.filter.filter(x.into(), z.into());
+        let angle = z.atan2(x); // think + FRAC_PI_2, but this offset is
+                                // compensated in the selector's thresholds
+        self.selector.select(angle)
+    }
+
+    /// Forgets about previous samples, e.g. when a new game starts. The calibration is kept.
+    pub fn reset(&mut self) {
+        self.filter.reset();
+        self.selector.reset();
+    }
+}
+
+impl Default for Tilt {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_SMOOTHING, Self::DEFAULT_BAND)
+    }
+}
+
+/// Averages accelerometer samples to find the board's neutral inclination, see
+/// [`Tilt::calibrate`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Calibrator {
+    x: i32,
+    z: i32,
+    samples: u8,
+}
+
+impl Calibrator {
+    /// Number of samples to average, i.e. three seconds worth of samples at 25 Hz.
+    pub const SAMPLES: u8 = 75;
+
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            x: 0,
+            z: 0,
+            samples: 0,
+        }
+    }
+
+    /// Accounts for the sample `(x, z)`, returning the average once enough samples have been
+    /// collected.
+    pub fn add(&mut self, x: i16, z: i16) -> Option<(i16, i16)> {
+        self.x += i32::from(x);
+        self.z += i32::from(z);
+        self.samples += 1;
+        if self.samples < Self::SAMPLES {
+            return None;
+        }
+        let samples = i32::from(self.samples);
+        // the average of i16 values fits into an i16
+        #[allow(clippy::cast_possible_truncation)]
+        let average = ((self.x / samples) as i16, (self.z / samples) as i16);
+        Some(average)
+    }
+}
@@ -1,4 +1,4 @@
-#![no_main]
+#![cfg_attr(feature = "device", no_main)]
 #![no_std]
 // The following lints are disabled (=`allow`ed) for the moment being. Turn them
 // active once you start documenting the public interface properly.
@@ -9,21 +9,28 @@
     clippy::missing_panics_doc
 )]
 
+#[cfg(feature = "device")]
 use core::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "device")]
 use defmt_rtt as _; // global logger
 
+#[cfg(feature = "device")]
 use panic_probe as _;
 
+#[cfg(feature = "device")]
 use microbit as _; // memory layout
 
 // same panicking *behavior* as `panic-probe` but doesn't print a panic message
 // this prevents the panic message being printed *twice* when `defmt::panic` is invoked
+#[cfg(feature = "device")]
 #[defmt::panic_handler]
 fn panic() -> ! {
     cortex_m::asm::udf()
 }
 
+#[cfg(feature = "device")]
 static COUNT: AtomicUsize = AtomicUsize::new(0);
+#[cfg(feature = "device")]
 defmt::timestamp!("{=usize}", {
     // NOTE(no-CAS) `timestamps` runs with interrupts disabled
     let n = COUNT.load(Ordering::Relaxed);
@@ -32,12 +39,18 @@ defmt::timestamp!("{=usize}", {
 });
 
 /// Terminates the application and makes `probe-run` exit with exit-code = 0
+#[cfg(feature = "device")]
 pub fn exit() -> ! {
     loop {
         cortex_m::asm::bkpt();
     }
 }
 
+mod log;
+
+pub mod cli;
+#[cfg(feature = "device")]
 pub mod device;
 pub mod game;
+pub mod settings;
 pub mod util;
@@ -0,0 +1,57 @@
+//! Logging macros for the hardware-independent parts of the crate.
+//!
+//! When built for the micro:bit, the macros forward to their [`defmt`] counterparts. On the host
+//! (i.e. without the `defmt` feature) there is no global logger to forward to, so the macros
+//! expand to nothing.
+
+#[cfg(feature = "defmt")]
+macro_rules! trace {
+    ($($arg:tt)*) => { ::defmt::trace!($($arg)*) };
+}
+
+#[cfg(not(feature = "defmt"))]
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        ()
+    };
+}
+
+#[cfg(feature = "defmt")]
+macro_rules! debug {
+    ($($arg:tt)*) => { ::defmt::debug!($($arg)*) };
+}
+
+#[cfg(not(feature = "defmt"))]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        ()
+    };
+}
+
+#[cfg(feature = "defmt")]
+macro_rules! info {
+    ($($arg:tt)*) => { ::defmt::info!($($arg)*) };
+}
+
+#[cfg(not(feature = "defmt"))]
+macro_rules! info {
+    ($($arg:tt)*) => {
+        ()
+    };
+}
+
+#[cfg(feature = "defmt")]
+macro_rules! warning {
+    ($($arg:tt)*) => { ::defmt::warn!($($arg)*) };
+}
+
+#[cfg(not(feature = "defmt"))]
+macro_rules! warning {
+    ($($arg:tt)*) => {
+        ()
+    };
+}
+
+// A plain `warn` would be ambiguous with the built-in `#[warn]` attribute, hence the renaming.
+#[allow(unused_imports)]
+pub(crate) use {debug, info, trace, warning as warn};
@@ -0,0 +1,266 @@
+//! Settings surviving a reset, persisted in a [`Store`](store::Store).
+//!
+//! The settings are hardware-independent, so that their record format can be tested on the host.
+//! The flash backing them on the micro:bit lives in `device::storage`.
+
+use crate::game::{
+    shading::{DisplayStyle, Shading},
+    speed::Speed,
+    tilt::TiltSettings,
+};
+use core::{
+    cell::Cell,
+    fmt::{Debug, Formatter, Result as FmtResult},
+    ops::RangeInclusive,
+};
+use critical_section::Mutex;
+use store::Record;
+
+pub mod store;
+
+/// The settings adjustable by name, see [`Settings::get`] and [`Settings::set`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    SpeedPeriod,
+    SpeedDrop,
+    TiltHysteresis,
+    TiltSmoothing,
+    Seed,
+    SettledBrightness,
+    FallingBrightness,
+}
+
+impl Key {
+    pub const ALL: [Key; 7] = [
+        Key::SpeedPeriod,
+        Key::SpeedDrop,
+        Key::TiltHysteresis,
+        Key::TiltSmoothing,
+        Key::Seed,
+        Key::SettledBrightness,
+        Key::FallingBrightness,
+    ];
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Key::SpeedPeriod => "speed.period",
+            Key::SpeedDrop => "speed.drop",
+            Key::TiltHysteresis => "tilt.hysteresis",
+            Key::TiltSmoothing => "tilt.smoothing",
+            Key::Seed => "game.seed",
+            Key::SettledBrightness => "display.settled",
+            Key::FallingBrightness => "display.falling",
+        }
+    }
+
+    /// The values [`Settings::set`] accepts for the key.
+    #[must_use]
+    pub fn range(self) -> RangeInclusive<u32> {
+        let widen = |range: RangeInclusive<u8>| u32::from(*range.start())..=u32::from(*range.end());
+        match self {
+            Key::SpeedPeriod => {
+                u32::from(*Speed::PERIODS.start())..=u32::from(*Speed::PERIODS.end())
+            }
+            Key::SpeedDrop => widen(Speed::SOFT_DROPS),
+            Key::TiltHysteresis => widen(TiltSettings::HYSTERESES),
+            Key::TiltSmoothing => widen(TiltSettings::SMOOTHINGS),
+            Key::Seed => 0..=u32::MAX,
+            Key::SettledBrightness | Key::FallingBrightness => widen(Shading::BRIGHTNESSES),
+        }
+    }
+}
+
+/// The value passed to [`Settings::set`] is out of the key's [`range`](Key::range).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutOfRange;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Settings {
+    pub speed: Speed,
+    pub tilt: TiltSettings,
+    /// The neutral accelerometer sample found by the last calibration, see
+    /// [`Tilt::calibrate`](crate::game::tilt::Tilt::calibrate).
+    pub calibration: Option<(i16, i16)>,
+    /// Seed of the tile sequence, 0 drawing a different one at every boot.
+    pub seed: u32,
+    pub shading: Shading,
+}
+
+impl Settings {
+    pub const DEFAULT: Self = Self {
+        speed: Speed::DEFAULT,
+        tilt: TiltSettings::DEFAULT,
+        calibration: None,
+        seed: 0,
+        shading: Shading::DEFAULT,
+    };
+
+    #[must_use]
+    pub fn get(&self, key: Key) -> u32 {
+        match key {
+            Key::SpeedPeriod => self.speed.period_ms.into(),
+            Key::SpeedDrop => self.speed.soft_drop.into(),
+            Key::TiltHysteresis => self.tilt.hysteresis.into(),
+            Key::TiltSmoothing => self.tilt.smoothing.into(),
+            Key::Seed => self.seed,
+            Key::SettledBrightness => self.shading.settled.into(),
+            Key::FallingBrightness => self.shading.falling.into(),
+        }
+    }
+
+    pub fn set(&mut self, key: Key, value: u32) -> Result<(), OutOfRange> {
+        if !key.range().contains(&value) {
+            return Err(OutOfRange);
+        }
+        // the ranges fit the fields
+        #[allow(clippy::cast_possible_truncation)]
+        match key {
+            Key::SpeedPeriod => self.speed.period_ms = value as u16,
+            Key::SpeedDrop => self.speed.soft_drop = value as u8,
+            Key::TiltHysteresis => self.tilt.hysteresis = value as u8,
+            Key::TiltSmoothing => self.tilt.smoothing = value as u8,
+            Key::Seed => self.seed = value,
+            Key::SettledBrightness => self.shading.settled = value as u8,
+            Key::FallingBrightness => self.shading.falling = value as u8,
+        }
+        Ok(())
+    }
+
+    /// Resets all the settings adjustable by name, keeping the calibration.
+    pub fn reset(&mut self) {
+        *self = Self {
+            calibration: self.calibration,
+            ..Self::DEFAULT
+        };
+    }
+}
+
+/// Version 1 predates the [`Shading`], which decodes to its defaults.
+impl Record for Settings {
+    const VERSION: u16 = 2;
+    const ENCODED_SIZE: usize = 16;
+
+    fn encode(&self, bytes: &mut [u8]) {
+        bytes[0..2].copy_from_slice(&self.speed.period_ms.to_le_bytes());
+        bytes[2] = self.speed.soft_drop;
+        bytes[3] = self.tilt.hysteresis;
+        bytes[4] = self.tilt.smoothing;
+        if let Some((x, z)) = self.calibration {
+            bytes[5] = 1;
+            bytes[6..8].copy_from_slice(&x.to_le_bytes());
+            bytes[8..10].copy_from_slice(&z.to_le_bytes());
+        }
+        bytes[10..14].copy_from_slice(&self.seed.to_le_bytes());
+        bytes[14] = match self.shading.style {
+            DisplayStyle::Blink => 0,
+            DisplayStyle::Dim => 1,
+            DisplayStyle::Both => 2,
+        };
+        // both brightnesses fit into a nibble
+        bytes[15] = self.shading.settled | (self.shading.falling << 4);
+    }
+
+    fn decode(version: u16, bytes: &[u8]) -> Option<Self> {
+        if !(1..=Self::VERSION).contains(&version) {
+            return None;
+        }
+        let bytes: &[u8; Self::ENCODED_SIZE] = bytes.try_into().ok()?;
+        let calibration = match bytes[5] {
+            0 => None,
+            1 => Some((
+                i16::from_le_bytes([bytes[6], bytes[7]]),
+                i16::from_le_bytes([bytes[8], bytes[9]]),
+            )),
+            _ => return None,
+        };
+
+        let style = match (version, bytes[14]) {
+            (1, _) => Shading::DEFAULT.style,
+            (_, 0) => DisplayStyle::Blink,
+            (_, 1) => DisplayStyle::Dim,
+            (_, 2) => DisplayStyle::Both,
+            _ => return None,
+        };
+
+        let mut settings = Self {
+            calibration,
+            shading: Shading {
+                style,
+                ..Shading::DEFAULT
+            },
+            ..Self::DEFAULT
+        };
+        let values = [
+            (
+                Key::SpeedPeriod,
+                u16::from_le_bytes([bytes[0], bytes[1]]).into(),
+            ),
+            (Key::SpeedDrop, bytes[2].into()),
+            (Key::TiltHysteresis, bytes[3].into()),
+            (Key::TiltSmoothing, bytes[4].into()),
+            (
+                Key::Seed,
+                u32::from_le_bytes([bytes[10], bytes[11], bytes[12], bytes[13]]),
+            ),
+        ];
+        for (key, value) in values {
+            settings.set(key, value).ok()?;
+        }
+        if version > 1 {
+            settings
+                .set(Key::SettledBrightness, (bytes[15] & 0x0f).into())
+                .ok()?;
+            settings
+                .set(Key::FallingBrightness, (bytes[15] >> 4).into())
+                .ok()?;
+        }
+        Some(settings)
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// [`Settings`] shared between the CLI and whoever applies or persists them.
+pub struct SharedSettings(Mutex<Cell<Settings>>);
+
+impl SharedSettings {
+    #[must_use]
+    pub const fn new(settings: Settings) -> Self {
+        Self(Mutex::new(Cell::new(settings)))
+    }
+
+    #[must_use]
+    pub fn get(&self) -> Settings {
+        critical_section::with(|cs| self.0.borrow(cs).get())
+    }
+
+    pub fn update<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut Settings) -> R,
+    {
+        critical_section::with(|cs| {
+            let cell = self.0.borrow(cs);
+            let mut settings = cell.get();
+            let result = f(&mut settings);
+            cell.set(settings);
+            result
+        })
+    }
+}
+
+impl Default for SharedSettings {
+    fn default() -> Self {
+        Self::new(Settings::DEFAULT)
+    }
+}
+
+impl Debug for SharedSettings {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_tuple("SharedSettings").field(&self.get()).finish()
+    }
+}
@@ -0,0 +1,245 @@
+//! Versioned records persisted in flash, e.g. the [`Settings`](super::Settings).
+//!
+//! Every save appends a record to one of two flash pages. Once the page is full, the other page is
+//! erased and written from its start, so that each page is erased only every other time a page
+//! fills up. The newest valid record, as per its sequence number, is the one in effect.
+//!
+//! A record consists of (all integers little endian)
+//!
+//! | bytes | content                                            |
+//! |-------|----------------------------------------------------|
+//! | 4     | [`MAGIC`]                                          |
+//! | 2     | format version, see [`Record::VERSION`]            |
+//! | 2     | length of the encoded data                         |
+//! | 4     | sequence number                                    |
+//! | n     | encoded data, padded to a multiple of 4 bytes      |
+//! | 4     | CRC-32 of all the preceding bytes                  |
+//!
+//! A record torn by a reset while writing fails the CRC check and is skipped, leaving the previous
+//! record in effect. Records of other format versions may differ in length, they are handed to
+//! [`Record::decode`] as they are, so that the data survives firmware upgrades.
+
+/// Page-wise erasable flash, e.g. the NVMC. Offsets and lengths passed to
+/// [`write`](Self::write) are multiples of 4 bytes.
+pub trait Flash {
+    type Error;
+
+    /// Size of a page in bytes.
+    const PAGE_SIZE: usize;
+
+    fn read(&mut self, page: usize, offset: usize, bytes: &mut [u8]) -> Result<(), Self::Error>;
+
+    /// Programs `bytes`, which have to be erased before. Like NOR flash, programming may only
+    /// clear bits.
+    fn write(&mut self, page: usize, offset: usize, bytes: &[u8]) -> Result<(), Self::Error>;
+
+    /// Sets all of the page's bits.
+    fn erase(&mut self, page: usize) -> Result<(), Self::Error>;
+}
+
+/// Data kept in a [`Store`].
+pub trait Record: Sized {
+    /// Version of the [`encode`](Self::encode)d format. Bump it whenever the format changes.
+    const VERSION: u16;
+    /// Length of the encoded data, at most [`MAX_ENCODED_SIZE`].
+    const ENCODED_SIZE: usize;
+
+    /// Encodes `self` into `bytes`, which are zeroed and [`ENCODED_SIZE`](Self::ENCODED_SIZE)
+    /// bytes long.
+    fn encode(&self, bytes: &mut [u8]);
+
+    /// Decodes data [`encode`](Self::encode)d by the given `version`, checking every value's
+    /// range. Older versions, which may differ in length, have to be decoded as well. Unknown
+    /// versions and invalid values yield `None`.
+    fn decode(version: u16, bytes: &[u8]) -> Option<Self>;
+}
+
+/// CRC-32 (IEEE 802.3) of `bytes`.
+#[must_use]
+pub fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0, |crc, byte| {
+        (0..8).fold(crc ^ u32::from(*byte), |crc, _| {
+            (crc >> 1) ^ (0xedb8_8320 & 0u32.wrapping_sub(crc & 1))
+        })
+    })
+}
+
+/// Marks the start of a record.
+pub const MAGIC: u32 = u32::from_le_bytes(*b"MTST");
+/// Largest [`Record::ENCODED_SIZE`] supported.
+pub const MAX_ENCODED_SIZE: usize = 512;
+const HEADER_SIZE: usize = 12;
+const CRC_SIZE: usize = 4;
+const MAX_RECORD_SIZE: usize = record_size(MAX_ENCODED_SIZE);
+const PAGES: usize = 2;
+
+/// Size of a record holding `encoded_size` bytes of data.
+#[must_use]
+pub const fn record_size(encoded_size: usize) -> usize {
+    HEADER_SIZE + encoded_size.next_multiple_of(4) + CRC_SIZE
+}
+
+/// Where the next record goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Cursor {
+    page: usize,
+    offset: usize,
+}
+
+/// Persists a [`Record`] in two pages of [`Flash`], see the [module docs](self).
+///
+/// The flash is passed to each call, so that several stores can share it.
+#[derive(Debug)]
+pub struct Store<R> {
+    first_page: usize,
+    // `None` if there is no valid record
+    cursor: Option<Cursor>,
+    sequence: u32,
+    record: Option<R>,
+}
+
+impl<R> Store<R>
+where
+    R: Record,
+{
+    const RECORD_SIZE: usize = record_size(R::ENCODED_SIZE);
+
+    /// Scans the pages `first_page` and `first_page + 1` for the newest valid record.
+    pub fn open<F>(flash: &mut F, first_page: usize) -> Result<Self, F::Error>
+    where
+        F: Flash,
+    {
+        const { assert!(R::ENCODED_SIZE <= MAX_ENCODED_SIZE) };
+
+        let mut newest: Option<(u32, R, Cursor)> = None;
+        let mut buffer = [0; MAX_RECORD_SIZE];
+        for page in first_page..first_page + PAGES {
+            let mut offset = 0;
+            while offset + HEADER_SIZE <= F::PAGE_SIZE {
+                let header = &mut buffer[..HEADER_SIZE];
+                flash.read(page, offset, header)?;
+                if header.iter().all(|byte| *byte == 0xff) {
+                    break;
+                }
+                // Whatever follows an unknown header or a record overrunning the page cannot be
+                // made sense of, so the page is treated as full.
+                let Some(length) = record_length(header)
+                    .filter(|length| *length <= MAX_RECORD_SIZE && offset + length <= F::PAGE_SIZE)
+                else {
+                    offset = F::PAGE_SIZE;
+                    break;
+                };
+                let record = &mut buffer[..length];
+                flash.read(page, offset, record)?;
+                if let Some((sequence, data)) = decode(record) {
+                    if newest
+                        .as_ref()
+                        .is_none_or(|(newest, _, _)| sequence > *newest)
+                    {
+                        newest = Some((sequence, data, Cursor { page, offset }));
+                    }
+                }
+                offset += length;
+            }
+            // The cursor points past the page's last record, provided it is the newest's page.
+            if let Some((_, _, cursor)) = newest.as_mut() {
+                if cursor.page == page {
+                    cursor.offset = offset;
+                }
+            }
+        }
+
+        Ok(match newest {
+            Some((sequence, record, cursor)) => Self {
+                first_page,
+                cursor: Some(cursor),
+                sequence,
+                record: Some(record),
+            },
+            None => Self {
+                first_page,
+                cursor: None,
+                sequence: 0,
+                record: None,
+            },
+        })
+    }
+
+    /// The data of the newest valid record, if any.
+    #[must_use]
+    pub fn get(&self) -> Option<&R> {
+        self.record.as_ref()
+    }
+
+    /// Appends a record holding `data`.
+    ///
+    /// Note: erasing a page of the nRF52833's flash stalls the CPU for up to 85 ms.
+    pub fn save<F>(&mut self, flash: &mut F, data: R) -> Result<(), F::Error>
+    where
+        F: Flash,
+    {
+        let sequence = self.sequence.wrapping_add(1);
+        let mut buffer = [0; MAX_RECORD_SIZE];
+        let record = &mut buffer[..Self::RECORD_SIZE];
+        encode(sequence, &data, record);
+
+        let cursor = match self.cursor {
+            Some(cursor) if cursor.offset + Self::RECORD_SIZE <= F::PAGE_SIZE => cursor,
+            // Never erase the page holding the newest record, in case writing the new one fails.
+            full => {
+                let page = full.map_or(self.first_page, |cursor| {
+                    self.first_page + (cursor.page - self.first_page + 1) % PAGES
+                });
+                flash.erase(page)?;
+                Cursor { page, offset: 0 }
+            }
+        };
+        // Should the write fail, the slot is skipped, as it may hold a torn record.
+        self.cursor = Some(Cursor {
+            offset: cursor.offset + Self::RECORD_SIZE,
+            ..cursor
+        });
+        flash.write(cursor.page, cursor.offset, record)?;
+
+        self.sequence = sequence;
+        self.record = Some(data);
+        Ok(())
+    }
+}
+
+fn record_length(record: &[u8]) -> Option<usize> {
+    let magic = u32::from_le_bytes(record[0..4].try_into().ok()?);
+    let length = usize::from(u16::from_le_bytes(record[6..8].try_into().ok()?));
+    (magic == MAGIC).then(|| record_size(length))
+}
+
+fn encode<R>(sequence: u32, data: &R, record: &mut [u8])
+where
+    R: Record,
+{
+    record[0..4].copy_from_slice(&MAGIC.to_le_bytes());
+    record[4..6].copy_from_slice(&R::VERSION.to_le_bytes());
+    #[allow(clippy::cast_possible_truncation)]
+    let length = R::ENCODED_SIZE as u16;
+    record[6..8].copy_from_slice(&length.to_le_bytes());
+    record[8..12].copy_from_slice(&sequence.to_le_bytes());
+    data.encode(&mut record[HEADER_SIZE..HEADER_SIZE + R::ENCODED_SIZE]);
+    let crc_at = record.len() - CRC_SIZE;
+    let crc = crc32(&record[..crc_at]);
+    record[crc_at..].copy_from_slice(&crc.to_le_bytes());
+}
+
+fn decode<R>(record: &[u8]) -> Option<(u32, R)>
+where
+    R: Record,
+{
+    let crc_at = record.len() - CRC_SIZE;
+    if crc32(&record[..crc_at]).to_le_bytes() != record[crc_at..] {
+        return None;
+    }
+    let version = u16::from_le_bytes([record[4], record[5]]);
+    let length = usize::from(u16::from_le_bytes([record[6], record[7]]));
+    let sequence = u32::from_le_bytes([record[8], record[9], record[10], record[11]]);
+    let data = record.get(HEADER_SIZE..HEADER_SIZE + length)?;
+    Some((sequence, R::decode(version, data)?))
+}
@@ -1,87 +1,79 @@
-use core::{cmp::min, str::FromStr};
-use futures::{task::Poll, Future};
+use core::fmt::{Error, Result as FmtResult, Write};
 use heapless::String;
-use nb::{Error, Result as NbResult};
 
-struct BusyWait<F> {
-    f: F,
+/// [`Write`] adapter collecting a single chunk of formatted text, see [`Chunks`].
+pub struct ChunkWriter<const N: usize> {
+    // bytes to skip, i.e. the length of the previous chunks
+    skip: usize,
+    // bytes formatted so far, including the skipped ones
+    seen: usize,
+    chunk: String<N>,
 }
 
-impl<F> BusyWait<F> {
-    fn new(f: F) -> Self {
-        Self { f }
-    }
-}
-
-// TODO: am I allowed to implement Unpin?!
-impl<F> Unpin for BusyWait<F> {}
+impl<const N: usize> Write for ChunkWriter<N> {
+    fn write_str(&mut self, s: &str) -> FmtResult {
+        let start = self.skip.saturating_sub(self.seen).min(s.len());
+        self.seen += s.len();
+        // The chunks end on character boundaries, so the skipped part does as well.
+        let rest = s.get(start..).ok_or(Error)?;
 
-// `BusyWait`'s implementation of Future works by busy waiting for the result to
-// become ready. I.e. in the poll method, when obtaining a `Error::WouldBlock`
-// value, we wake the owning task directly again.
-// Note that while this works for RTIC's executor, it may well lead to
-// starvation of other tasks when executed on other executors.
-// Even with RITC, this implementation relies on undocumented behaviour!
-impl<F, T, E> Future for BusyWait<F>
-where
-    F: FnMut() -> NbResult<T, E>,
-{
-    type Output = Result<T, E>;
-
-    fn poll(
-        mut self: core::pin::Pin<&mut Self>,
-        cx: &mut core::task::Context<'_>,
-    ) -> Poll<Self::Output> {
-        (self.f)().map_or_else(
-            |e| match e {
-                Error::WouldBlock => {
-                    cx.waker().wake_by_ref();
-                    Poll::Pending
-                }
-                Error::Other(e) => Poll::Ready(Err(e)),
-            },
-            |val| Poll::Ready(Ok(val)),
-        )
+        let room = N - self.chunk.len();
+        if rest.len() <= room {
+            self.chunk.push_str(rest).map_err(|()| Error)
+        } else {
+            let mut end = room;
+            while !rest.is_char_boundary(end) {
+                end -= 1;
+            }
+            self.chunk.push_str(&rest[..end]).map_err(|()| Error)?;
+            // The chunk is full, there is no point in formatting the remainder.
+            Err(Error)
+        }
     }
 }
 
-pub fn nb_async<F, T, E>(f: F) -> impl Future<Output = Result<T, E>>
-where
-    F: FnMut() -> NbResult<T, E>,
-{
-    BusyWait::new(f)
-}
-
-pub struct StringIter<'a, const N: usize> {
-    raw: &'a str,
-    cursor: usize,
+/// Cuts the text formatted by `format` into chunks of at most `N` bytes, never splitting a
+/// character.
+///
+/// The text is formatted anew for each chunk, skipping what the previous chunks covered. Hence no
+/// buffer for the whole text is needed and the text may be of any length, as long as `format`
+/// formats the same text each time. Should `format` fail, the text ends at the point of failure.
+pub struct Chunks<const N: usize, F> {
+    format: F,
+    offset: usize,
 }
 
-impl<'a, const N: usize, P> From<&'a P> for StringIter<'a, N>
+impl<const N: usize, F> Chunks<N, F>
 where
-    P: AsRef<str>,
+    F: FnMut(&mut ChunkWriter<N>) -> FmtResult,
 {
-    fn from(value: &'a P) -> Self {
-        Self {
-            raw: value.as_ref(),
-            cursor: 0,
-        }
+    #[must_use]
+    pub fn new(format: F) -> Self {
+        Self { format, offset: 0 }
     }
 }
 
-impl<'a, const N: usize> Iterator for StringIter<'a, N> {
-    type Item = Result<String<N>, ()>;
+impl<const N: usize, F> Iterator for Chunks<N, F>
+where
+    F: FnMut(&mut ChunkWriter<N>) -> FmtResult,
+{
+    type Item = String<N>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.cursor < self.raw.len() {
-            let chunk_length = min(N, self.raw.len() - self.cursor);
-            let res = Some(String::<N>::from_str(
-                &self.raw[self.cursor..(self.cursor + chunk_length)],
-            ));
-            self.cursor += chunk_length;
-            res
-        } else {
+        let mut writer = ChunkWriter {
+            skip: self.offset,
+            seen: 0,
+            chunk: String::new(),
+        };
+        // Formatting fails once the chunk is full, so the result tells nothing about whether the
+        // text is complete.
+        let _ = (self.format)(&mut writer);
+
+        if writer.chunk.is_empty() {
             None
+        } else {
+            self.offset += writer.chunk.len();
+            Some(writer.chunk)
         }
     }
 }
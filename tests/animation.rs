@@ -0,0 +1,144 @@
+//! Host-side tests of the keyframe animations played on the board.
+//! Run them via `cargo test-host` (see `.cargo/config.toml`).
+
+use microtile_app::game::{
+    animation::{Animation, Effect, Keyframe, ROW_CLEAR, TILE_LOCK},
+    board::Board,
+    shading::{DisplayStyle, ShadedBoard, Shading},
+};
+
+const SETTLED: u8 = 8;
+
+/// A full bottom row below a vertical pair of cells in the leftmost column.
+fn board() -> Board {
+    let mut board = Board::empty();
+    for column in 0..Board::COLUMNS {
+        board.set(0, column);
+    }
+    board.set(1, 0);
+    board.set(2, 0);
+    board
+}
+
+fn row(animation: &Animation, row: usize) -> Vec<u8> {
+    (0..Board::COLUMNS)
+        .map(|column| animation.brightness(row, column, SETTLED))
+        .collect()
+}
+
+/// Advances `animation` until done, returning the number of frames it took.
+fn play(animation: &mut Animation) -> usize {
+    let mut frames = 0;
+    while !animation.is_done() {
+        animation.advance();
+        frames += 1;
+        assert!(frames <= 100, "animation should end");
+    }
+    frames
+}
+
+#[test]
+fn keyframes_last_their_number_of_frames() {
+    const KEYFRAMES: &[Keyframe] = &[
+        Keyframe::new(Effect::Fill(9), 2),
+        Keyframe::new(Effect::Fill(0), 1),
+    ];
+    let mut animation = Animation::new(KEYFRAMES, board(), board());
+
+    assert_eq!(row(&animation, 0), [9; 5]);
+    animation.advance();
+    assert_eq!(row(&animation, 0), [9; 5]);
+    animation.advance();
+    assert_eq!(row(&animation, 0), [0; 5]);
+    animation.advance();
+    assert!(animation.is_done());
+    // a finished animation shows the frozen board
+    assert_eq!(row(&animation, 0), [SETTLED; 5]);
+    animation.advance();
+    assert!(animation.is_done());
+}
+
+#[test]
+fn landing_with_full_rows_clears_them() {
+    let mut tile = Board::empty();
+    tile.set(0, 3);
+    tile.set(0, 4);
+    let mut animation = Animation::landing(tile, board());
+    assert_eq!(
+        animation,
+        Animation::new(ROW_CLEAR, board().full_row_cells(), board())
+    );
+
+    // the rows above are left alone
+    assert_eq!(row(&animation, 1), [SETTLED, 0, 0, 0, 0]);
+    // flash
+    assert_eq!(row(&animation, 0), [9; 5]);
+    animation.advance();
+    assert_eq!(row(&animation, 0), [0; 5]);
+    animation.advance();
+    animation.advance();
+    // sweep
+    assert_eq!(row(&animation, 0), [0, 0, SETTLED, SETTLED, SETTLED]);
+    assert_eq!(row(&animation, 1), [SETTLED, 0, 0, 0, 0]);
+    assert_eq!(play(&mut animation), 2);
+}
+
+#[test]
+fn landing_without_full_rows_locks_the_tile() {
+    let mut tile = Board::empty();
+    tile.set(2, 0);
+    let mut board = tile;
+    board.set(1, 0);
+    let mut animation = Animation::landing(tile, board);
+    assert_eq!(animation, Animation::new(TILE_LOCK, tile, board));
+
+    // fades in, rounding up
+    assert_eq!(animation.brightness(2, 0, SETTLED), 4);
+    assert_eq!(animation.brightness(1, 0, SETTLED), SETTLED);
+    animation.advance();
+    assert_eq!(animation.brightness(2, 0, SETTLED), 7);
+    assert_eq!(animation.brightness(2, 0, 1), 1);
+    assert_eq!(play(&mut animation), 1);
+}
+
+#[test]
+fn falling_tile_is_shown_on_top() {
+    let mut animation = Animation::landing(Board::empty(), board());
+    animation.advance();
+    let mut active = Board::empty();
+    active.set(0, 1);
+    active.set(4, 2);
+    let shading = Shading {
+        style: DisplayStyle::Both,
+        ..Shading::DEFAULT
+    };
+
+    let lit = ShadedBoard::new(active, Board::empty(), shading, true);
+    assert_eq!(animation.brightness_below(4, 2, &lit), shading.falling);
+    assert_eq!(animation.brightness_below(0, 1, &lit), shading.falling);
+    // the flashing row goes on around the tile
+    assert_eq!(animation.brightness_below(0, 0, &lit), 0);
+    assert_eq!(animation.brightness_below(1, 0, &lit), shading.settled);
+
+    // unlit frames of a blinking tile reveal the animation
+    let unlit = ShadedBoard::new(active, Board::empty(), shading, false);
+    assert_eq!(animation.brightness_below(4, 2, &unlit), 0);
+    assert_eq!(
+        animation.brightness_below(0, 1, &unlit),
+        animation.brightness(0, 1, shading.settled)
+    );
+}
+
+#[test]
+fn full_row_cells_cover_whole_rows() {
+    let mut board = board();
+    assert_eq!(board.full_row_cells().count(), 5);
+    for column in 0..Board::COLUMNS {
+        board.set(3, column);
+    }
+    let rows = board.full_row_cells();
+    assert_eq!(rows.count(), 10);
+    assert!(rows.is_set(3, 4));
+    assert!(!rows.is_set(1, 0));
+    assert!(Board::empty().full_row_cells().is_empty());
+}
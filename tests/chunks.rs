@@ -0,0 +1,69 @@
+//! Host-side tests of [`Chunks`], which cuts the CLI's output into uplink messages.
+//!
+//! Run them via `cargo test-host` (see `.cargo/config.toml`).
+
+use core::fmt::{Error, Write};
+use microtile_app::util::Chunks;
+
+const N: usize = 8;
+
+fn chunks(text: &str) -> Vec<String> {
+    Chunks::<N, _>::new(|out| out.write_str(text))
+        .map(|chunk| chunk.as_str().to_owned())
+        .collect()
+}
+
+#[test]
+fn short_text_is_a_single_chunk() {
+    assert_eq!(chunks("hello"), ["hello"]);
+    assert_eq!(chunks("12345678"), ["12345678"]);
+}
+
+#[test]
+fn empty_text_has_no_chunks() {
+    assert!(chunks("").is_empty());
+}
+
+#[test]
+fn long_text_is_cut_into_full_chunks() {
+    assert_eq!(chunks("0123456789abcdefXY"), ["01234567", "89abcdef", "XY"]);
+}
+
+#[test]
+fn characters_are_never_split() {
+    // 'ä' takes two bytes, starting at the chunk's last byte
+    let chunks = chunks("1234567ä89");
+    assert_eq!(chunks, ["1234567", "ä89"]);
+}
+
+#[test]
+fn formatted_text_is_reassembled() {
+    let numbers = Chunks::<N, _>::new(|out| {
+        for i in 0..200 {
+            write!(out, "{i}, ")?;
+        }
+        out.write_str("€")
+    });
+    let mut expected = String::new();
+    for i in 0..200 {
+        write!(expected, "{i}, ").unwrap();
+    }
+    expected.push('€');
+
+    let mut reassembled = String::new();
+    for chunk in numbers {
+        assert!(!chunk.is_empty() && chunk.len() <= N);
+        reassembled.push_str(&chunk);
+    }
+    assert_eq!(reassembled, expected);
+}
+
+#[test]
+fn failing_format_ends_text() {
+    let chunks = Chunks::<N, _>::new(|out| {
+        out.write_str("0123456789")?;
+        Err(Error)
+    })
+    .collect::<Vec<_>>();
+    assert_eq!(chunks, ["01234567", "89"]);
+}
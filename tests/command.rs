@@ -0,0 +1,292 @@
+//! Host-side tests of the CLI's command parsing.
+
+use microtile_app::{
+    cli::{
+        command::{Command, CommandError, Import, Scores, Switch, Token},
+        parse::{Arguments, Assignment, Keyword},
+    },
+    game::{record::Entry, shading::DisplayStyle, snapshot::Step},
+    settings::Key,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Style {
+    Blink,
+    Dim,
+}
+
+impl Keyword for Style {
+    const KEYWORDS: &'static [(&'static str, Self)] = &[("blink", Self::Blink), ("dim", Self::Dim)];
+}
+
+fn parse(line: &str) -> Result<Command, CommandError> {
+    Command::try_from(line.as_bytes())
+}
+
+#[test]
+fn commands_without_arguments() {
+    assert_eq!(parse("ver"), Ok(Command::Version));
+    assert_eq!(parse("  help "), Ok(Command::Help));
+    assert_eq!(parse("record"), Ok(Command::Record(None)));
+    assert_eq!(parse("board"), Ok(Command::Board));
+    assert_eq!(parse("reset"), Ok(Command::Reset));
+    assert_eq!(parse("save"), Ok(Command::Save));
+    assert_eq!(parse("defaults"), Ok(Command::Defaults));
+    assert_eq!(parse("scores"), Ok(Command::Scores(Scores::List)));
+}
+
+#[test]
+fn commands_with_arguments() {
+    assert_eq!(parse("record 10"), Ok(Command::Record(Some(10))));
+    assert_eq!(parse("record\t 3"), Ok(Command::Record(Some(3))));
+    assert_eq!(parse("mirror"), Ok(Command::Mirror(None)));
+    assert_eq!(parse("mirror off"), Ok(Command::Mirror(Some(Switch::Off))));
+    assert_eq!(
+        parse("speed"),
+        Ok(Command::Speed {
+            period_ms: None,
+            soft_drop: None
+        })
+    );
+    assert_eq!(
+        parse("speed 500"),
+        Ok(Command::Speed {
+            period_ms: Some(500),
+            soft_drop: None
+        })
+    );
+    assert_eq!(
+        parse("speed 100 10"),
+        Ok(Command::Speed {
+            period_ms: Some(100),
+            soft_drop: Some(10)
+        })
+    );
+    assert_eq!(parse("get"), Ok(Command::Get(None)));
+    assert_eq!(
+        parse("get speed.drop"),
+        Ok(Command::Get(Some(Key::SpeedDrop)))
+    );
+    assert_eq!(
+        parse("set tilt.hysteresis 3"),
+        Ok(Command::Set(Key::TiltHysteresis, 3))
+    );
+    assert_eq!(
+        parse("set game.seed 4294967295"),
+        Ok(Command::Set(Key::Seed, u32::MAX))
+    );
+    assert_eq!(parse("scores clear"), Ok(Command::Scores(Scores::Clear)));
+    assert_eq!(parse("display"), Ok(Command::Display(None)));
+    assert_eq!(
+        parse("display both"),
+        Ok(Command::Display(Some(DisplayStyle::Both)))
+    );
+    assert_eq!(
+        parse("set display.falling 2"),
+        Ok(Command::Set(Key::FallingBrightness, 2))
+    );
+}
+
+#[test]
+fn malformed_commands_are_rejected() {
+    assert_eq!(
+        parse("version"),
+        Err(CommandError::UnknownCommand(Token::from("version")))
+    );
+    assert_eq!(
+        parse(""),
+        Err(CommandError::UnknownCommand(Token::from("")))
+    );
+    assert_eq!(
+        parse("ver 1"),
+        Err(CommandError::UnexpectedArgument(Token::from("1")))
+    );
+    assert_eq!(
+        parse("record ten"),
+        Err(CommandError::InvalidNumber(Token::from("ten")))
+    );
+    assert_eq!(
+        parse("record -1"),
+        Err(CommandError::InvalidNumber(Token::from("-1")))
+    );
+    assert_eq!(
+        parse("speed 99"),
+        Err(CommandError::InvalidNumber(Token::from("99")))
+    );
+    assert_eq!(
+        parse("speed 1000 0"),
+        Err(CommandError::InvalidNumber(Token::from("0")))
+    );
+    assert_eq!(
+        parse("set tilt.hysteresis 6"),
+        Err(CommandError::InvalidNumber(Token::from("6")))
+    );
+    assert_eq!(
+        parse("set tilt 1"),
+        Err(CommandError::InvalidKeyword(Token::from("tilt")))
+    );
+    assert_eq!(
+        parse("set speed.period"),
+        Err(CommandError::MissingArgument)
+    );
+    assert_eq!(
+        parse("set display.settled 0"),
+        Err(CommandError::InvalidNumber(Token::from("0")))
+    );
+    assert_eq!(
+        parse("display bright"),
+        Err(CommandError::InvalidKeyword(Token::from("bright")))
+    );
+    assert_eq!(
+        Command::try_from(&b"ver\xff"[..]),
+        Err(CommandError::InvalidEncoding)
+    );
+}
+
+#[test]
+fn recording_lines_are_imported() {
+    assert_eq!(
+        parse("import seed: 42"),
+        Ok(Command::Import(Import::Seed(Some(42))))
+    );
+    assert_eq!(
+        parse("import seed: -"),
+        Ok(Command::Import(Import::Seed(None)))
+    );
+    assert_eq!(
+        parse("import ticks: 120"),
+        Ok(Command::Import(Import::Ticks(120)))
+    );
+    assert_eq!(
+        parse("import entries: 0..57"),
+        Ok(Command::Import(Import::Entries { first: 0, end: 57 }))
+    );
+    assert_eq!(
+        parse("import 3 rotate"),
+        Ok(Command::Import(Import::Entry(Entry {
+            tick: 3,
+            step: Step::Rotate
+        })))
+    );
+    assert_eq!(
+        parse("import 17 column 4"),
+        Ok(Command::Import(Import::Entry(Entry {
+            tick: 17,
+            step: Step::MoveTo(4)
+        })))
+    );
+
+    // whatever `record` prints can be imported
+    for entry in [
+        Entry {
+            tick: 9,
+            step: Step::MoveTo(0),
+        },
+        Entry {
+            tick: 0,
+            step: Step::Rotate,
+        },
+    ] {
+        assert_eq!(
+            parse(&format!("import {entry}")),
+            Ok(Command::Import(Import::Entry(entry)))
+        );
+    }
+}
+
+#[test]
+fn malformed_recording_lines_are_rejected() {
+    assert_eq!(parse("import"), Err(CommandError::MissingArgument));
+    assert_eq!(parse("import seed:"), Err(CommandError::MissingArgument));
+    assert_eq!(
+        parse("import entries: 5..2"),
+        Err(CommandError::InvalidNumber(Token::from("5..2")))
+    );
+    assert_eq!(
+        parse("import 3 column 5"),
+        Err(CommandError::InvalidNumber(Token::from("5")))
+    );
+    assert_eq!(
+        parse("import 3 tick"),
+        Err(CommandError::InvalidKeyword(Token::from("tick")))
+    );
+    assert_eq!(
+        parse("import three rotate"),
+        Err(CommandError::InvalidNumber(Token::from("three")))
+    );
+    assert_eq!(
+        parse("import 3 rotate 1"),
+        Err(CommandError::UnexpectedArgument(Token::from("1")))
+    );
+}
+
+#[test]
+fn arguments_are_parsed_in_order() {
+    let mut args = Arguments::new("5 dim x=-3 rest");
+    assert_eq!(args.required::<u8>(), Ok(5));
+    assert_eq!(args.required::<Style>(), Ok(Style::Dim));
+    assert_eq!(
+        args.required::<Assignment<'_, i16>>(),
+        Ok(Assignment {
+            key: "x",
+            value: -3
+        })
+    );
+    assert_eq!(
+        args.clone().finish(),
+        Err(CommandError::UnexpectedArgument(Token::from("rest")))
+    );
+    assert_eq!(args.required::<&str>(), Ok("rest"));
+    assert_eq!(args.optional::<u8>(), Ok(None));
+    assert_eq!(args.required::<u8>(), Err(CommandError::MissingArgument));
+    assert_eq!(args.finish(), Ok(()));
+}
+
+#[test]
+fn invalid_arguments_are_rejected() {
+    assert_eq!(
+        Arguments::new("300").required::<u8>(),
+        Err(CommandError::InvalidNumber(Token::from("300")))
+    );
+    assert_eq!(
+        Arguments::new("bright").required::<Style>(),
+        Err(CommandError::InvalidKeyword(Token::from("bright")))
+    );
+    assert_eq!(
+        Arguments::new("=1").required::<Assignment<'_, u8>>(),
+        Err(CommandError::InvalidAssignment(Token::from("=1")))
+    );
+    assert_eq!(
+        Arguments::new("x").required::<Assignment<'_, u8>>(),
+        Err(CommandError::InvalidAssignment(Token::from("x")))
+    );
+    assert_eq!(
+        Arguments::new("x=y").required::<Assignment<'_, u8>>(),
+        Err(CommandError::InvalidNumber(Token::from("y")))
+    );
+}
+
+#[test]
+fn keywords_round_trip() {
+    assert_eq!(Style::Blink.keyword(), "blink");
+    assert_eq!(Style::Dim.keyword(), "dim");
+    for key in Key::ALL {
+        assert_eq!(key.keyword(), key.name());
+    }
+    for style in [DisplayStyle::Blink, DisplayStyle::Dim, DisplayStyle::Both] {
+        assert_eq!(style.keyword(), style.name());
+    }
+}
+
+#[test]
+fn errors_name_the_offending_input() {
+    assert_eq!(
+        parse("record 12x").unwrap_err().to_string(),
+        "invalid number '12x'"
+    );
+    assert_eq!(
+        parse("supercalifragilistic").unwrap_err().to_string(),
+        "unknown command 'supercalifragi..'"
+    );
+    assert_eq!(Token::from("0123456789abcdef").as_str(), "0123456789abcdef");
+}
@@ -0,0 +1,1038 @@
+//! Host-side tests of [`GameDriver`].
+//!
+//! The tests feed scripted sequences of [`Message`]s through an `rtic_sync` channel and inspect
+//! the boards and events the driver reports to its [`Observer`] and [`Listener`] respectively.
+//!
+//! Run them via `cargo test-host` (see `.cargo/config.toml`).
+
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures::task::noop_waker_ref;
+use microtile_app::game::{
+    clock::PlayClock,
+    driver::{DriverError, GameDriver, MAILBOX_CAPACITY},
+    event::{Event, Listener},
+    highscore::SharedHighScores,
+    message::Message,
+    record::{Recording, SharedRecording},
+    snapshot::{Journal, SharedSnapshot, Snapshot, Step},
+    speed::Speed,
+    status::{Phase, SharedStatus},
+    tile::{ConstantProducer, Lookahead, RandomProducer, TileKind, TileProducer},
+    tilt::{tilt_towards, Calibrator, TiltSettings},
+};
+use microtile_engine::{
+    gameplay::game::Observer,
+    geometry::{grid::Grid, tile::BasicTile},
+};
+use rtic_sync::channel::{Channel, Sender};
+use std::sync::{Arc, Mutex};
+
+const ROWS: usize = 5;
+const COLUMNS: usize = 5;
+/// Time after a game over during which the driver refuses to start a new game.
+const GRACE_MICROS: u32 = 3_000_000;
+
+/// Plain copy of a [`Grid`], indexed by `[row][column]` with row 0 being the bottom row.
+type Board = [[bool; COLUMNS]; ROWS];
+
+fn to_board(grid: &Grid) -> Board {
+    let mut board = Board::default();
+    for (row, cells) in board.iter_mut().enumerate() {
+        for (column, cell) in cells.iter_mut().enumerate() {
+            *cell = grid.is_element_set(row, column).unwrap_or(false);
+        }
+    }
+    board
+}
+
+fn count(board: &Board) -> usize {
+    board.iter().flatten().filter(|cell| **cell).count()
+}
+
+fn is_empty(board: &Board) -> bool {
+    count(board) == 0
+}
+
+fn occupies_column(board: &Board, column: usize) -> bool {
+    board.iter().any(|cells| cells[column])
+}
+
+fn shift_down(board: &Board) -> Board {
+    let mut shifted = Board::default();
+    shifted[..ROWS - 1].copy_from_slice(&board[1..]);
+    shifted
+}
+
+fn shift_left(board: &Board) -> Board {
+    let mut shifted = Board::default();
+    for (shifted, cells) in shifted.iter_mut().zip(board) {
+        shifted[..COLUMNS - 1].copy_from_slice(&cells[1..]);
+    }
+    shifted
+}
+
+/// Pseudo-random, yet reproducible sequence of inputs, mimicking a player.
+fn script(length: usize) -> Vec<Message> {
+    let mut state: u32 = 0x1234_5678;
+    (0..length)
+        .map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            match (state >> 16) % 4 {
+                0 | 1 => Message::TimerTick,
+                2 => Message::BtnBPress,
+                #[allow(clippy::cast_possible_truncation)]
+                _ => tilt_towards(((state >> 8) % 5) as u8),
+            }
+        })
+        .collect()
+}
+
+/// [`Observer`] and [`Listener`] recording every board change and event it gets signalled.
+#[derive(Debug, Clone, Default)]
+struct Recorder {
+    boards: Arc<Mutex<Vec<(Board, Board)>>>,
+    events: Arc<Mutex<Vec<Event>>>,
+}
+
+impl Recorder {
+    fn latest(&self) -> Option<(Board, Board)> {
+        self.boards.lock().unwrap().last().copied()
+    }
+
+    fn events(&self) -> Vec<Event> {
+        self.events.lock().unwrap().clone()
+    }
+}
+
+impl Observer for Recorder {
+    fn signal_board_changed(&self, active: Grid, passive: Grid) {
+        self.boards
+            .lock()
+            .unwrap()
+            .push((to_board(&active), to_board(&passive)));
+    }
+}
+
+impl Listener for Recorder {
+    fn signal_event(&self, event: Event) {
+        self.events.lock().unwrap().push(event);
+    }
+}
+
+type Run = Pin<Box<dyn Future<Output = Result<(), DriverError>>>>;
+
+/// Drives a [`GameDriver`] one [`Message`] at a time.
+///
+/// Instead of running the driver on an executor, the harness polls [`GameDriver::run`] by hand
+/// after each message. This way, every message is guaranteed to be fully processed before the
+/// next one is fed.
+struct Harness {
+    sender: Sender<'static, Message, MAILBOX_CAPACITY>,
+    run: Run,
+    recorder: Recorder,
+    status: &'static SharedStatus,
+    recording: &'static SharedRecording,
+    clock: &'static PlayClock,
+    high_scores: &'static SharedHighScores,
+    snapshots: &'static SharedSnapshot,
+}
+
+impl Harness {
+    fn new<P>(producer: P) -> Self
+    where
+        P: TileProducer + 'static,
+    {
+        Self::resuming(producer, None)
+    }
+
+    /// Sets up a driver resuming the game `snapshot` has been taken of, if any.
+    fn resuming<P>(producer: P, snapshot: Option<Snapshot>) -> Self
+    where
+        P: TileProducer + 'static,
+    {
+        let channel = Box::leak(Box::new(Channel::new()));
+        let (sender, receiver) = channel.split();
+        let recorder = Recorder::default();
+        let status = Box::leak(Box::new(SharedStatus::new()));
+        let recording = Box::leak(Box::new(SharedRecording::new()));
+        let clock = Box::leak(Box::new(PlayClock::new()));
+        let high_scores = Box::leak(Box::new(SharedHighScores::new()));
+        let snapshots = Box::leak(Box::new(SharedSnapshot::new()));
+        snapshots.update(|shared| *shared = snapshot);
+        let driver = Box::leak(Box::new(
+            GameDriver::new(receiver, recorder.clone(), producer, status)
+                .with_recording(recording)
+                .with_clock(clock)
+                .with_high_scores(high_scores)
+                .with_snapshots(snapshots),
+        ));
+
+        let mut harness = Self {
+            sender,
+            run: Box::pin(driver.run()),
+            recorder,
+            status,
+            recording,
+            clock,
+            high_scores,
+            snapshots,
+        };
+        harness.poll();
+        harness
+    }
+
+    fn poll(&mut self) {
+        let mut cx = Context::from_waker(noop_waker_ref());
+        assert!(
+            self.run.as_mut().poll(&mut cx).is_pending(),
+            "driver should keep running as long as there is a sender"
+        );
+    }
+
+    /// Feeds `msg` and returns the latest boards once the driver is done processing it.
+    fn feed(&mut self, msg: Message) -> (Board, Board) {
+        assert!(
+            self.sender.try_send(msg).is_ok(),
+            "mailbox should accept message"
+        );
+        self.poll();
+        self.recorder
+            .latest()
+            .expect("driver should have signalled a board")
+    }
+
+    fn play<I>(&mut self, script: I) -> Vec<(Board, Board)>
+    where
+        I: IntoIterator<Item = Message>,
+    {
+        script.into_iter().map(|msg| self.feed(msg)).collect()
+    }
+
+    /// Ticks until the floating tile has landed and returns the boards right after landing.
+    fn land(&mut self) -> (Board, Board) {
+        for _ in 0..=ROWS {
+            let boards = self.feed(Message::TimerTick);
+            if !is_empty(&boards.1) {
+                return boards;
+            }
+        }
+        panic!("tile should have landed");
+    }
+
+    fn recorded(&self) -> Vec<Step> {
+        self.recording
+            .read(|recording| recording.entries().map(|entry| entry.step).collect())
+    }
+
+    /// Lets the grace period after a game over pass.
+    fn wait_out_grace(&mut self) {
+        self.clock.advance(GRACE_MICROS);
+    }
+
+    /// Ticks until the game is over.
+    fn lose(&mut self) {
+        // Without any horizontal movement, the tiles stack up in the same columns and never
+        // complete a row, so the game is bound to end.
+        let games_lost = self.status.get().games_lost;
+        for _ in 0..100 {
+            self.feed(Message::TimerTick);
+            if self.status.get().games_lost > games_lost {
+                return;
+            }
+        }
+        panic!("game should have ended");
+    }
+}
+
+#[test]
+fn tick_descends_tile() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+
+    let boards = harness.play([Message::TimerTick, Message::TimerTick]);
+    let (before, _) = boards[0];
+    let (after, passive) = boards[1];
+
+    assert!(!is_empty(&before));
+    assert_eq!(after, shift_down(&before));
+    assert!(is_empty(&passive));
+}
+
+#[test]
+fn acceleration_moves_tile_to_column() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+    harness.feed(Message::TimerTick);
+
+    let (active, _) = harness.feed(tilt_towards(0));
+    assert!(occupies_column(&active, 0));
+
+    // the samples are smoothed, so it takes a few of them to get all the way across
+    let (active, _) = harness
+        .play((0..10).map(|_| tilt_towards(4)))
+        .pop()
+        .expect("there should be samples");
+    assert!(occupies_column(&active, 4));
+    assert!(!occupies_column(&active, 0));
+}
+
+#[test]
+fn single_sample_does_not_move_tile_across() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+    harness.feed(Message::TimerTick);
+    harness.play((0..10).map(|_| tilt_towards(0)));
+
+    let (active, _) = harness.feed(tilt_towards(4));
+    assert!(!occupies_column(&active, 4));
+}
+
+#[test]
+fn moves_take_precedence_over_unchanged_tilt() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+    harness.feed(Message::TimerTick);
+    let (before, _) = harness
+        .play((0..10).map(|_| tilt_towards(2)))
+        .pop()
+        .expect("there should be samples");
+
+    let (moved, _) = harness.feed(Message::MoveLeft);
+    assert_eq!(moved, shift_left(&before));
+    assert_eq!(harness.feed(tilt_towards(2)).0, moved);
+    let moved_to = harness.status.get().phase;
+
+    // tilting towards another column takes over again
+    let (active, _) = harness
+        .play((0..10).map(|_| tilt_towards(4)))
+        .pop()
+        .expect("there should be samples");
+    assert!(occupies_column(&active, 4));
+    // both the move and the tilt are recorded as the columns they moved the tile to
+    let columns: Vec<_> = [moved_to, harness.status.get().phase]
+        .into_iter()
+        .map(|phase| match phase {
+            Phase::TileFloating { column } => Step::MoveTo(column),
+            _ => panic!("tile should be floating"),
+        })
+        .collect();
+    assert!(harness.recorded().ends_with(&columns));
+}
+
+#[test]
+fn landed_tile_becomes_passive() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+
+    let (active, passive) = harness.land();
+
+    assert_eq!(count(&passive), 4);
+    // the follow-up tile has not been placed yet
+    assert!(is_empty(&active));
+}
+
+#[test]
+fn landing_is_signalled() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+
+    harness.land();
+
+    let passive = harness.status.get().passive;
+    assert_eq!(
+        harness.recorder.events(),
+        [Event::TileLanded {
+            tile: passive,
+            board: passive
+        }]
+    );
+}
+
+#[test]
+fn rotation_is_ignored_without_floating_tile() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+
+    let landed = harness.land();
+
+    assert_eq!(harness.feed(Message::BtnBPress), landed);
+}
+
+#[test]
+fn game_over_waits_for_restart() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+
+    harness.lose();
+    let events = harness.recorder.events();
+    assert!(matches!(events.last(), Some(Event::GameOver { .. })));
+
+    // neither ticks nor input start the next game during the grace period
+    let over = harness.recorder.latest();
+    harness.play([Message::BtnBPress, tilt_towards(0), Message::TimerTick]);
+    assert_eq!(harness.recorder.latest(), over);
+    assert_eq!(harness.recorder.events(), events);
+}
+
+#[test]
+fn button_starts_new_game_after_grace_period() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+
+    harness.lose();
+    harness.wait_out_grace();
+    let (active, passive) = harness.feed(Message::BtnBPress);
+
+    assert_eq!(count(&active), 4);
+    assert!(is_empty(&passive));
+    assert_eq!(harness.recorder.events().last(), Some(&Event::GameStarted));
+}
+
+#[test]
+fn grace_period_is_timed_rather_than_ticked() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+
+    harness.lose();
+    // soft dropping ticks way faster than the level does
+    harness.play((0..20).map(|_| Message::TimerTick));
+    harness.clock.advance(GRACE_MICROS - 1);
+    harness.feed(Message::BtnBPress);
+    assert_eq!(harness.status.get().phase, Phase::GameOver);
+
+    harness.clock.advance(1);
+    harness.feed(Message::BtnBPress);
+    assert_eq!(harness.recorder.events().last(), Some(&Event::GameStarted));
+}
+
+#[test]
+fn pause_freezes_game() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+    let running = harness.feed(Message::TimerTick);
+
+    let paused = harness.play([
+        Message::Pause,
+        Message::TimerTick,
+        tilt_towards(0),
+        Message::TimerTick,
+    ]);
+    assert!(paused.iter().all(|boards| *boards == running));
+
+    let (active, _) = harness.feed(Message::Resume);
+    assert_eq!(active, running.0);
+    let (active, _) = harness.feed(Message::TimerTick);
+    assert_eq!(active, shift_down(&running.0));
+
+    assert_eq!(harness.recorder.events(), [Event::Paused, Event::Resumed]);
+}
+
+#[test]
+fn toggle_pause_alternates() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+    harness.feed(Message::TimerTick);
+
+    harness.play([
+        Message::TogglePause,
+        Message::Pause,
+        Message::TogglePause,
+        Message::Resume,
+    ]);
+
+    assert_eq!(harness.recorder.events(), [Event::Paused, Event::Resumed]);
+}
+
+#[test]
+fn controls_are_counted_even_if_refused() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+    harness.feed(Message::TimerTick);
+    let controls = harness.status.get().controls;
+    let mut cx = Context::from_waker(noop_waker_ref());
+    let mut processed = Box::pin(harness.status.control_processed(controls));
+    assert!(processed.as_mut().poll(&mut cx).is_pending());
+
+    harness.feed(Message::Pause);
+    assert!(matches!(processed.as_mut().poll(&mut cx), Poll::Ready(status) if status.paused));
+
+    // a paused game can't be paused again, yet the second pause counts as processed, too
+    harness.play([Message::Pause, Message::TogglePause]);
+    let status = harness.status.get();
+    assert_eq!(status.controls, controls.wrapping_add(2));
+    assert!(!status.paused);
+}
+
+#[test]
+fn status_mirrors_boards() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+
+    let (active, passive) = harness.feed(Message::TimerTick);
+    let status = harness.status.get();
+
+    assert_eq!(status.active.count() as usize, count(&active));
+    assert_eq!(status.passive.count() as usize, count(&passive));
+    for (row, cells) in active.iter().enumerate() {
+        for (column, cell) in cells.iter().enumerate() {
+            assert_eq!(status.active.is_set(row, column), *cell);
+        }
+    }
+}
+
+#[test]
+fn status_reports_phase() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+    harness.play((0..10).map(|_| tilt_towards(0)));
+    assert_eq!(
+        harness.status.get().phase,
+        Phase::TileFloating { column: 0 }
+    );
+
+    harness.land();
+    assert_eq!(harness.status.get().phase, Phase::ProcessRows);
+
+    harness.lose();
+    assert_eq!(harness.status.get().phase, Phase::GameOver);
+}
+
+#[test]
+fn placed_tiles_are_counted() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+    assert_eq!(harness.status.get().score.tiles, 1);
+
+    harness.land();
+    // process the rows until the next tile is placed
+    let mut ticks = 0;
+    while harness.status.get().score.tiles < 2 {
+        harness.feed(Message::TimerTick);
+        ticks += 1;
+        assert!(ticks <= ROWS, "next tile should have been placed");
+    }
+
+    let score = harness.status.get().score;
+    assert_eq!(score.tiles, 2);
+    assert_eq!(score.rows, 0);
+    assert_eq!(score.points, 0);
+}
+
+#[test]
+fn game_over_reports_final_score() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+
+    harness.lose();
+
+    let status = harness.status.get();
+    assert_eq!(status.games_lost, 1);
+    assert_eq!(
+        harness.recorder.events().last(),
+        Some(&Event::GameOver {
+            score: status.score,
+            place: None
+        })
+    );
+    assert!(status.score.tiles > 1);
+}
+
+#[test]
+fn games_without_points_are_not_ranked() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+
+    harness.lose();
+
+    assert_eq!(harness.status.get().score.points, 0);
+    assert!(harness
+        .high_scores
+        .read(|scores| scores.entries().is_empty()));
+}
+
+#[test]
+fn play_clock_runs_only_while_playing() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+    harness.clock.advance(1_500_000);
+    assert_eq!(harness.clock.elapsed_secs(), 1);
+
+    harness.feed(Message::Pause);
+    harness.clock.advance(5_000_000);
+    assert_eq!(harness.clock.elapsed_secs(), 1);
+
+    harness.feed(Message::Resume);
+    harness.clock.advance(500_000);
+    assert_eq!(harness.clock.elapsed_secs(), 2);
+
+    harness.lose();
+    let played = harness.clock.elapsed_secs();
+    harness.clock.advance(5_000_000);
+    assert_eq!(harness.clock.elapsed_secs(), played);
+    // the uptime includes the pause and the time since the game ended
+    assert_eq!(harness.clock.uptime_micros(), 12_000_000);
+
+    harness.wait_out_grace();
+    harness.feed(Message::BtnBPress);
+    assert_eq!(harness.clock.elapsed_secs(), 0);
+}
+
+#[test]
+fn new_game_resets_score() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+
+    harness.lose();
+    harness.wait_out_grace();
+    harness.feed(Message::BtnBPress);
+
+    let status = harness.status.get();
+    assert_eq!(status.games_lost, 1);
+    assert_eq!(status.score.tiles, 1);
+    assert_eq!(status.score.level, 0);
+}
+
+#[test]
+fn status_reports_seed_of_current_game() {
+    let mut harness = Harness::new(RandomProducer::new(42));
+    assert_eq!(harness.status.get().seed, Some(42));
+
+    harness.lose();
+    assert_eq!(harness.status.get().seed, Some(42));
+
+    harness.wait_out_grace();
+    harness.feed(Message::BtnBPress);
+    let seed = harness.status.get().seed;
+    assert!(seed.is_some());
+    assert_ne!(seed, Some(42));
+}
+
+#[test]
+fn upcoming_tile_is_previewed() {
+    let mut harness = Harness::new(Lookahead::<_, 1>::new(ConstantProducer::new(
+        BasicTile::Square,
+    )));
+    assert_eq!(harness.status.get().next, Some(TileKind::Square));
+
+    harness.land();
+    let mut ticks = 0;
+    while harness.status.get().score.tiles < 2 {
+        harness.feed(Message::TimerTick);
+        ticks += 1;
+        assert!(ticks <= ROWS, "next tile should have been placed");
+    }
+
+    let previews: Vec<_> = harness
+        .recorder
+        .events()
+        .into_iter()
+        .filter(|event| matches!(event, Event::NextTile(_)))
+        .collect();
+    assert_eq!(previews, [Event::NextTile(TileKind::Square)]);
+}
+
+#[test]
+fn unknown_upcoming_tile_is_not_previewed() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+    assert_eq!(harness.status.get().next, None);
+
+    harness.lose();
+    assert!(harness
+        .recorder
+        .events()
+        .iter()
+        .all(|event| !matches!(event, Event::NextTile(_))));
+}
+
+#[test]
+fn recording_reproduces_game_elsewhere() {
+    let mut harness = Harness::new(RandomProducer::new(1234));
+    let (recorded, _) = harness
+        .play(script(200))
+        .pop()
+        .expect("script is not empty");
+    let ticks = harness.recording.read(Recording::ticks);
+
+    // the other device tilts differently, which the recorded columns don't care about
+    let mut replay = Harness::new(RandomProducer::new(1));
+    replay.feed(Message::SetTilt(TiltSettings {
+        smoothing: 100,
+        hysteresis: 0,
+    }));
+    replay.feed(Message::Pause);
+    let seed = harness.recording.read(Recording::seed);
+    let entries: Vec<_> = harness
+        .recording
+        .read(|recording| recording.entries().collect());
+    replay.recording.update(|recording| {
+        recording.restart(seed);
+        recording.set_ticks(ticks);
+        for entry in entries {
+            assert_eq!(recording.import(entry), Ok(()));
+        }
+    });
+    replay.feed(Message::Replay);
+    let (replayed, _) = replay
+        .play((0..=ticks).map(|_| Message::TimerTick))
+        .pop()
+        .expect("there should be ticks");
+
+    assert_eq!(replayed, recorded);
+    assert_eq!(replay.status.get().score, harness.status.get().score);
+}
+
+#[test]
+fn recording_stamps_steps_with_ticks() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+
+    harness.play([
+        Message::TimerTick,
+        tilt_towards(0),
+        Message::TimerTick,
+        Message::BtnBPress,
+    ]);
+
+    let entries: Vec<_> = harness
+        .recording
+        .read(|recording| recording.entries().collect());
+    let ticks: Vec<_> = entries.iter().map(|entry| entry.tick).collect();
+    assert_eq!(ticks, [1, 2]);
+    assert_eq!(harness.recorded(), [Step::MoveTo(0), Step::Rotate]);
+    assert_eq!(harness.recording.read(Recording::ticks), 2);
+}
+
+#[test]
+fn recording_skips_samples_keeping_the_column() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+    harness.feed(Message::TimerTick);
+
+    harness.play((0..50).map(|_| tilt_towards(0)));
+
+    assert_eq!(harness.recorded(), [Step::MoveTo(0)]);
+}
+
+#[test]
+fn recording_skips_pause() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+
+    harness.play([
+        Message::TimerTick,
+        Message::Pause,
+        Message::TimerTick,
+        tilt_towards(0),
+        Message::Resume,
+        Message::TimerTick,
+    ]);
+
+    assert!(harness.recorded().is_empty());
+    assert_eq!(harness.recording.read(Recording::ticks), 2);
+}
+
+#[test]
+fn recording_restarts_with_new_game() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+
+    harness.lose();
+    assert_ne!(harness.recording.read(Recording::ticks), 0);
+
+    harness.wait_out_grace();
+    harness.feed(Message::BtnBPress);
+    assert_eq!(harness.recording.read(Recording::ticks), 0);
+}
+
+#[test]
+fn replay_reproduces_game_at_recorded_pace() {
+    let mut harness = Harness::new(Lookahead::<_, 1>::new(RandomProducer::new(99)));
+    let (recorded, _) = harness
+        .play(script(100))
+        .pop()
+        .expect("script is not empty");
+    let score = harness.status.get().score;
+    let ticks = usize::try_from(harness.recording.read(Recording::ticks)).unwrap();
+
+    harness.feed(Message::Replay);
+    assert_eq!(harness.recorder.events().last(), Some(&Event::GameStarted));
+
+    // live input other than ticks is ignored while replaying
+    harness.feed(tilt_towards(0));
+    harness.feed(Message::BtnBPress);
+    let (replayed, _) = harness
+        .play((0..=ticks).map(|_| Message::TimerTick))
+        .pop()
+        .expect("there should be ticks");
+
+    assert_eq!(replayed, recorded);
+    assert_eq!(harness.status.get().score, score);
+}
+
+#[test]
+fn overwritten_recording_is_not_replayed() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+
+    // every rotation is recorded, no matter whether the tile turns
+    let boards = harness.play((0..=Recording::CAPACITY).map(|_| Message::BtnBPress));
+    assert!(!harness.recording.read(Recording::is_complete));
+
+    let events = harness.recorder.events();
+    assert_eq!(harness.feed(Message::Replay), *boards.last().unwrap());
+    assert_eq!(harness.recorder.events(), events);
+}
+
+#[test]
+fn button_is_ignored_during_pause() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+    let running = harness.feed(Message::TimerTick);
+
+    let paused = harness.play([Message::Pause, Message::BtnBPress]);
+    assert!(paused.iter().all(|boards| *boards == running));
+    assert_eq!(harness.recorder.events(), [Event::Paused]);
+    let status = harness.status.get();
+    assert!(status.paused);
+    assert!(!status.calibrating);
+}
+
+#[test]
+fn calibration_shifts_the_columns() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+    harness.feed(Message::TimerTick);
+
+    harness.feed(Message::Calibrate);
+    assert_eq!(harness.recorder.events(), [Event::CalibrationStarted]);
+
+    // the player holds the board tilted towards column 3, which becomes the new center
+    harness.play((0..Calibrator::SAMPLES).map(|_| tilt_towards(3)));
+    assert!(matches!(
+        harness.recorder.events().last(),
+        Some(Event::Calibrated { .. })
+    ));
+
+    harness.feed(Message::Resume);
+    let (active, _) = harness
+        .play((0..10).map(|_| tilt_towards(3)))
+        .pop()
+        .expect("there should be samples");
+    assert!(occupies_column(&active, 2));
+    assert!(!occupies_column(&active, 4));
+
+    // without calibration, the tile ends up further right
+    let mut uncalibrated = Harness::new(ConstantProducer::new(BasicTile::Square));
+    uncalibrated.feed(Message::TimerTick);
+    let (expected, _) = uncalibrated
+        .play((0..10).map(|_| tilt_towards(3)))
+        .pop()
+        .expect("there should be samples");
+    assert_ne!(active, expected);
+}
+
+#[test]
+fn calibration_pauses_game_and_holds_input() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+    let running = harness.feed(Message::TimerTick);
+
+    let calibrating = harness.play([
+        Message::Calibrate,
+        Message::Resume,
+        Message::TimerTick,
+        Message::BtnBPress,
+    ]);
+    assert!(calibrating.iter().all(|boards| *boards == running));
+    assert_eq!(harness.recorder.events(), [Event::CalibrationStarted]);
+    assert!(harness.status.get().paused);
+}
+
+#[test]
+fn implausible_calibration_fails() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+
+    harness.feed(Message::Calibrate);
+    // board held upright
+    harness.play((0..Calibrator::SAMPLES).map(|_| Message::acceleration(1000, 0)));
+
+    assert_eq!(
+        harness.recorder.events().last(),
+        Some(&Event::CalibrationFailed)
+    );
+}
+
+#[test]
+fn reset_cancels_calibration() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+    harness.feed(Message::TimerTick);
+    harness.feed(Message::Calibrate);
+    assert!(harness.status.get().calibrating);
+
+    harness.feed(Message::Reset);
+
+    let status = harness.status.get();
+    assert!(!status.calibrating);
+    assert!(!status.paused);
+    assert_eq!(
+        harness.recorder.events(),
+        [Event::CalibrationStarted, Event::GameStarted]
+    );
+    // the samples no longer go towards the calibration, but steer the new tile
+    let (before, _) = harness.feed(Message::TimerTick);
+    let (after, _) = harness
+        .play((0..Calibrator::SAMPLES).map(|_| tilt_towards(0)))
+        .pop()
+        .expect("there should be samples");
+    assert_ne!(before, after);
+    assert!(!harness
+        .recorder
+        .events()
+        .iter()
+        .any(|event| matches!(event, Event::Calibrated { .. } | Event::CalibrationFailed)));
+}
+
+#[test]
+fn reset_starts_new_game() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+    harness.land();
+    harness.feed(Message::Pause);
+
+    harness.feed(Message::Reset);
+
+    let status = harness.status.get();
+    assert!(!status.paused);
+    assert_eq!(status.games_lost, 0);
+    assert_eq!(status.score.tiles, 1);
+    assert!(harness.recorder.events().contains(&Event::GameStarted));
+    let (active, passive) = harness.recorder.latest().unwrap();
+    assert!(!is_empty(&active));
+    assert!(is_empty(&passive));
+}
+
+#[test]
+fn speed_changes_are_forwarded() {
+    let mut harness = Harness::new(ConstantProducer::new(BasicTile::Square));
+    let running = harness.feed(Message::TimerTick);
+
+    harness.feed(Message::Pause);
+    let speed = Speed {
+        period_ms: 500,
+        soft_drop: 2,
+    };
+    let paused = harness.feed(Message::SetSpeed(speed));
+
+    assert_eq!(paused, running);
+    assert_eq!(
+        harness.recorder.events().last(),
+        Some(&Event::SpeedChanged(speed))
+    );
+    assert_eq!(harness.status.get().speed, speed);
+}
+
+/// Plays a while and pauses, returning the snapshot taken on pause.
+fn paused_snapshot(harness: &mut Harness) -> Snapshot {
+    harness.play(script(40));
+    assert_ne!(
+        harness.status.get().phase,
+        Phase::GameOver,
+        "game should still be running"
+    );
+    harness.feed(Message::Pause);
+    harness
+        .snapshots
+        .read(Clone::clone)
+        .expect("pausing should take a snapshot")
+}
+
+#[test]
+fn pausing_takes_snapshot() {
+    let mut harness = Harness::new(RandomProducer::new(7));
+    let snapshot = paused_snapshot(&mut harness);
+
+    let status = harness.status.get();
+    assert_eq!(snapshot.seed, 7);
+    assert_eq!(snapshot.score, status.score);
+    assert_eq!(snapshot.active, status.active);
+    assert_eq!(snapshot.passive, status.passive);
+    assert_eq!(
+        harness.recorder.events().last(),
+        Some(&Event::SnapshotTaken)
+    );
+}
+
+#[test]
+fn game_over_drops_snapshot() {
+    let mut harness = Harness::new(RandomProducer::new(7));
+    paused_snapshot(&mut harness);
+    harness.feed(Message::Resume);
+
+    harness.lose();
+    assert_eq!(harness.snapshots.read(Clone::clone), None);
+}
+
+#[test]
+fn outgrown_journal_drops_snapshot() {
+    let mut harness = Harness::new(RandomProducer::new(7));
+    paused_snapshot(&mut harness);
+    harness.feed(Message::Resume);
+
+    // every rotation takes up a step of its own, no matter whether the tile turns
+    harness.play((0..Journal::CAPACITY).map(|_| Message::BtnBPress));
+
+    assert_eq!(harness.snapshots.read(Clone::clone), None);
+    assert!(harness
+        .recorder
+        .events()
+        .ends_with(&[Event::SnapshotTaken, Event::SnapshotsStopped]));
+    // pausing no longer takes snapshots
+    harness.feed(Message::Pause);
+    assert_eq!(harness.snapshots.read(Clone::clone), None);
+}
+
+#[test]
+fn snapshot_resumes_game() {
+    let mut harness = Harness::new(RandomProducer::new(1234));
+    let snapshot = paused_snapshot(&mut harness);
+
+    let mut resumed = Harness::resuming(RandomProducer::new(1), Some(snapshot));
+    let (original, status) = (harness.status.get(), resumed.status.get());
+    assert_eq!(status.active, original.active);
+    assert_eq!(status.passive, original.passive);
+    assert_eq!(status.score, original.score);
+    assert_eq!(status.seed, Some(1234));
+    assert!(status.paused);
+    assert_eq!(
+        resumed.recorder.events().last(),
+        Some(&Event::Restored(original.score))
+    );
+    assert!(!resumed.recording.read(Recording::is_complete));
+
+    // both games go on the same way
+    harness.feed(Message::Resume);
+    resumed.feed(Message::Resume);
+    for _ in 0..10 {
+        assert_eq!(
+            resumed.feed(Message::TimerTick),
+            harness.feed(Message::TimerTick)
+        );
+    }
+}
+
+#[test]
+fn button_declines_restored_game() {
+    let mut harness = Harness::new(RandomProducer::new(1234));
+    let snapshot = paused_snapshot(&mut harness);
+
+    let mut resumed = Harness::resuming(RandomProducer::new(1), Some(snapshot));
+    resumed.feed(Message::BtnBPress);
+
+    let status = resumed.status.get();
+    assert!(!status.paused);
+    assert_eq!(status.score.tiles, 1);
+    assert!(resumed.recorder.events().contains(&Event::GameStarted));
+    assert_eq!(resumed.snapshots.read(Clone::clone), None);
+}
+
+#[test]
+fn button_is_ignored_once_restored_game_is_resumed() {
+    let mut harness = Harness::new(RandomProducer::new(1234));
+    let snapshot = paused_snapshot(&mut harness);
+
+    let mut resumed = Harness::resuming(RandomProducer::new(1), Some(snapshot));
+    resumed.feed(Message::Resume);
+    let paused = resumed.feed(Message::Pause);
+    assert_eq!(resumed.feed(Message::BtnBPress), paused);
+    assert!(resumed.status.get().paused);
+    assert!(!resumed.recorder.events().contains(&Event::GameStarted));
+}
+
+#[test]
+fn mismatching_snapshot_is_discarded() {
+    let mut harness = Harness::new(RandomProducer::new(1234));
+    let mut snapshot = paused_snapshot(&mut harness);
+    snapshot.score.tiles += 1;
+
+    let resumed = Harness::resuming(RandomProducer::new(1), Some(snapshot));
+    let status = resumed.status.get();
+    assert!(!status.paused);
+    assert_eq!(status.score.tiles, 1);
+    assert!(resumed.recorder.events().contains(&Event::GameStarted));
+    assert_eq!(resumed.snapshots.read(Clone::clone), None);
+}
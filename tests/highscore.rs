@@ -0,0 +1,212 @@
+//! Host-side tests of the high score table and its record format.
+//! Run them via `cargo test-host` (see `.cargo/config.toml`).
+
+use microtile_app::{
+    game::highscore::{Entry, HighScores},
+    settings::store::{Flash, Record, Store},
+};
+
+const PAGE_SIZE: usize = 512;
+
+/// Two pages of flash in RAM.
+struct RamFlash([[u8; PAGE_SIZE]; 2]);
+
+impl Flash for RamFlash {
+    type Error = ();
+
+    const PAGE_SIZE: usize = PAGE_SIZE;
+
+    fn read(&mut self, page: usize, offset: usize, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        bytes.copy_from_slice(&self.0[page][offset..offset + bytes.len()]);
+        Ok(())
+    }
+
+    fn write(&mut self, page: usize, offset: usize, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.0[page][offset..offset + bytes.len()].copy_from_slice(bytes);
+        Ok(())
+    }
+
+    fn erase(&mut self, page: usize) -> Result<(), Self::Error> {
+        self.0[page] = [0xff; PAGE_SIZE];
+        Ok(())
+    }
+}
+
+/// Stand-in for a future format version, holding two more entries.
+#[derive(Debug, PartialEq)]
+struct Grown(HighScores);
+
+impl Record for Grown {
+    const VERSION: u16 = HighScores::VERSION + 1;
+    const ENCODED_SIZE: usize = HighScores::ENCODED_SIZE + 2 * 16;
+
+    fn encode(&self, bytes: &mut [u8]) {
+        self.0.encode(&mut bytes[..HighScores::ENCODED_SIZE]);
+    }
+
+    fn decode(version: u16, bytes: &[u8]) -> Option<Self> {
+        // the entries are laid out the same way
+        HighScores::decode(version.min(HighScores::VERSION), bytes).map(Self)
+    }
+}
+
+fn entry(points: u32) -> Entry {
+    Entry {
+        points,
+        rows: points / 40,
+        duration_secs: points / 10,
+        seed: Some(points),
+    }
+}
+
+fn points(scores: &HighScores) -> Vec<u32> {
+    scores.entries().iter().map(|entry| entry.points).collect()
+}
+
+fn encode(scores: &HighScores) -> [u8; HighScores::ENCODED_SIZE] {
+    let mut bytes = [0; HighScores::ENCODED_SIZE];
+    scores.encode(&mut bytes);
+    bytes
+}
+
+#[test]
+fn entries_are_kept_best_first() {
+    let mut scores = HighScores::new();
+    assert_eq!(scores.insert(entry(100)), Some(0));
+    assert_eq!(scores.insert(entry(300)), Some(0));
+    assert_eq!(scores.insert(entry(200)), Some(1));
+
+    assert_eq!(points(&scores), [300, 200, 100]);
+}
+
+#[test]
+fn ties_rank_below_earlier_games() {
+    let mut scores = HighScores::new();
+    scores.insert(entry(100));
+    let later = Entry {
+        seed: None,
+        ..entry(100)
+    };
+
+    assert_eq!(scores.insert(later), Some(1));
+    assert_eq!(scores.entries()[0], entry(100));
+    assert_eq!(scores.entries()[1], later);
+}
+
+#[test]
+fn full_table_drops_the_worst_entry() {
+    let mut scores = HighScores::new();
+    for points in 1..=5 {
+        scores.insert(entry(points * 100));
+    }
+
+    assert_eq!(scores.place(100), None);
+    assert_eq!(scores.insert(entry(100)), None);
+    assert_eq!(scores.insert(entry(250)), Some(3));
+    assert_eq!(points(&scores), [500, 400, 300, 250, 200]);
+}
+
+#[test]
+fn games_without_points_never_rank() {
+    let mut scores = HighScores::new();
+    assert_eq!(scores.insert(entry(0)), None);
+    assert!(scores.entries().is_empty());
+}
+
+#[test]
+fn clearing_empties_the_table() {
+    let mut scores = HighScores::new();
+    scores.insert(entry(100));
+    scores.clear();
+
+    assert!(scores.entries().is_empty());
+    assert_eq!(scores.place(1), Some(0));
+}
+
+#[test]
+fn high_scores_are_encoded_losslessly() {
+    let mut scores = HighScores::new();
+    assert_eq!(
+        HighScores::decode(HighScores::VERSION, &encode(&scores)),
+        Some(scores.clone())
+    );
+
+    scores.insert(entry(u32::MAX));
+    scores.insert(Entry {
+        seed: None,
+        ..entry(120)
+    });
+    scores.insert(entry(40));
+    assert_eq!(
+        HighScores::decode(HighScores::VERSION, &encode(&scores)),
+        Some(scores)
+    );
+}
+
+#[test]
+fn corrupted_tables_are_not_decoded() {
+    let mut scores = HighScores::new();
+    scores.insert(entry(200));
+    scores.insert(entry(100));
+    let bytes = encode(&scores);
+    assert_eq!(HighScores::decode(HighScores::VERSION + 1, &bytes), None);
+    assert_eq!(HighScores::decode(HighScores::VERSION, &bytes[1..]), None);
+
+    let mut too_many = bytes;
+    too_many[0] = 6;
+    assert_eq!(HighScores::decode(HighScores::VERSION, &too_many), None);
+
+    let mut out_of_order = bytes;
+    out_of_order[4..8].copy_from_slice(&50u32.to_le_bytes());
+    assert_eq!(HighScores::decode(HighScores::VERSION, &out_of_order), None);
+}
+
+#[test]
+fn tables_of_other_sizes_are_decoded() {
+    let mut scores = HighScores::new();
+    for points in 1..=5 {
+        scores.insert(entry(points * 100));
+    }
+    let mut grown = [0; HighScores::ENCODED_SIZE + 16];
+    scores.encode(&mut grown[..HighScores::ENCODED_SIZE]);
+    assert_eq!(
+        HighScores::decode(HighScores::VERSION, &grown),
+        Some(scores.clone())
+    );
+
+    // a sixth entry, which no longer makes it into the table
+    grown[0] = 6;
+    grown[HighScores::ENCODED_SIZE..HighScores::ENCODED_SIZE + 4]
+        .copy_from_slice(&50u32.to_le_bytes());
+    assert_eq!(
+        HighScores::decode(HighScores::VERSION, &grown),
+        Some(scores)
+    );
+}
+
+#[test]
+fn records_of_other_sizes_and_versions_are_opened() {
+    let mut flash = RamFlash([[0xff; PAGE_SIZE]; 2]);
+    let mut scores = HighScores::new();
+    scores.insert(entry(300));
+    scores.insert(entry(100));
+    let mut store = Store::<HighScores>::open(&mut flash, 0).unwrap();
+    store.save(&mut flash, scores.clone()).unwrap();
+
+    // upgrading to the next version keeps the table
+    let mut upgraded = Store::<Grown>::open(&mut flash, 0).unwrap();
+    assert_eq!(upgraded.get(), Some(&Grown(scores.clone())));
+
+    // the record it saves is unknown to the earlier version, which keeps its own
+    let mut improved = scores.clone();
+    improved.insert(entry(200));
+    upgraded.save(&mut flash, Grown(improved.clone())).unwrap();
+    assert_eq!(
+        Store::<Grown>::open(&mut flash, 0).unwrap().get(),
+        Some(&Grown(improved))
+    );
+    assert_eq!(
+        Store::<HighScores>::open(&mut flash, 0).unwrap().get(),
+        Some(&scores)
+    );
+}
@@ -0,0 +1,43 @@
+//! Host-side tests of playing from the terminal's keyboard.
+
+use microtile_app::{
+    cli::keys::{Key, KeyDecoder},
+    game::message::Message,
+};
+
+fn keys(input: &[u8]) -> Vec<Key> {
+    let mut decoder = KeyDecoder::new();
+    input
+        .iter()
+        .filter_map(|byte| decoder.feed(*byte))
+        .collect()
+}
+
+#[test]
+fn arrow_keys_are_decoded() {
+    assert_eq!(
+        keys(b"\x1b[A\x1b[B\x1b[C\x1b[D"),
+        [Key::Up, Key::Down, Key::Right, Key::Left]
+    );
+    // application cursor mode
+    assert_eq!(keys(b"\x1bOD"), [Key::Left]);
+}
+
+#[test]
+fn other_sequences_are_swallowed() {
+    assert_eq!(
+        keys(b"\x1b[1;5C \x1b[3~p\x1bxq"),
+        [Key::Right, Key::Char(b' '), Key::Char(b'p'), Key::QUIT]
+    );
+}
+
+#[test]
+fn keys_map_onto_messages() {
+    assert_eq!(Key::Left.message(), Some(Message::MoveLeft));
+    assert_eq!(Key::Right.message(), Some(Message::MoveRight));
+    assert_eq!(Key::Up.message(), Some(Message::BtnBPress));
+    assert_eq!(Key::Char(b' ').message(), Some(Message::BtnBPress));
+    assert_eq!(Key::Down.message(), Some(Message::TimerTick));
+    assert_eq!(Key::Char(b'p').message(), Some(Message::TogglePause));
+    assert_eq!(Key::QUIT.message(), None);
+}
@@ -0,0 +1,66 @@
+//! Host-side tests of the CLI's line editing.
+
+use microtile_app::cli::line::{Edit, LineEditor};
+
+const SIZE: usize = 8;
+
+/// Feeds `input` byte by byte, collecting the submitted lines.
+fn lines(editor: &mut LineEditor<SIZE>, input: &[u8]) -> Vec<Vec<u8>> {
+    input
+        .iter()
+        .filter_map(|byte| match editor.feed(*byte) {
+            Edit::Submit(line) => Some(line.to_vec()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn printable_input_is_echoed() {
+    let mut editor = LineEditor::<SIZE>::new();
+    assert_eq!(editor.feed(b'v'), Edit::Echo(b'v'));
+    assert_eq!(editor.feed(b' '), Edit::Echo(b' '));
+    assert_eq!(editor.feed(0x07), Edit::None);
+}
+
+#[test]
+fn every_terminator_submits() {
+    let mut editor = LineEditor::<SIZE>::new();
+    assert_eq!(
+        lines(&mut editor, b"ver\rhelp\npause;"),
+        [&b"ver"[..], b"help", b"pause"]
+    );
+}
+
+#[test]
+fn crlf_submits_once() {
+    let mut editor = LineEditor::<SIZE>::new();
+    assert_eq!(lines(&mut editor, b"ver\r\n\r\n"), [&b"ver"[..], b""]);
+}
+
+#[test]
+fn backspace_and_delete_erase() {
+    let mut editor = LineEditor::<SIZE>::new();
+    assert_eq!(editor.feed(0x08), Edit::None);
+    assert_eq!(lines(&mut editor, b"vex\x08"), Vec::<Vec<u8>>::new());
+    assert_eq!(editor.feed(0x7f), Edit::Erase);
+    assert_eq!(lines(&mut editor, b"er;"), [b"ver"]);
+}
+
+#[test]
+fn escape_sequences_are_ignored() {
+    let mut editor = LineEditor::<SIZE>::new();
+    assert_eq!(editor.feed(0x1b), Edit::None);
+    assert_eq!(editor.feed(b'['), Edit::None);
+    assert_eq!(editor.feed(b'A'), Edit::None);
+    assert_eq!(lines(&mut editor, b"v\x1b[1;5Cer\r"), [b"ver"]);
+}
+
+#[test]
+fn overflow_discards_line() {
+    let mut editor = LineEditor::<SIZE>::new();
+    assert_eq!(lines(&mut editor, b"12345678"), Vec::<Vec<u8>>::new());
+    assert_eq!(editor.feed(b'9'), Edit::Overflow);
+    assert_eq!(editor.feed(b'0'), Edit::None);
+    assert_eq!(lines(&mut editor, b"abc;ver;"), [b"ver"]);
+}
@@ -0,0 +1,86 @@
+//! Host-side tests of the live view of the board.
+
+use microtile_app::{
+    cli::mirror::{render, FRAME_CAPACITY, HEIGHT},
+    game::{
+        board::Board,
+        score::Score,
+        status::{Phase, Status},
+    },
+};
+
+fn frame(status: &Status) -> String {
+    let mut frame = String::new();
+    render(&mut frame, status).expect("rendering into a string should not fail");
+    frame
+}
+
+/// The board's rows from top to bottom, stripped of any escape sequences.
+fn rows(frame: &str) -> Vec<String> {
+    let mut plain = String::new();
+    let mut chars = frame.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // skip up to and including the final byte
+            if chars.next() == Some('[') {
+                for c in chars.by_ref() {
+                    if c.is_ascii_alphabetic() {
+                        break;
+                    }
+                }
+            }
+        } else {
+            plain.push(c);
+        }
+    }
+    plain
+        .split("\r\n")
+        .filter(|line| line.starts_with('|'))
+        .map(str::to_owned)
+        .collect()
+}
+
+#[test]
+fn frame_covers_height_and_restores_cursor() {
+    let frame = frame(&Status::new());
+
+    assert_eq!(frame.matches("\r\n").count(), HEIGHT - 1);
+    assert!(frame.starts_with("\x1b7"));
+    assert!(frame.ends_with("\x1b8"));
+    assert_eq!(rows(&frame), vec!["|          |"; Board::ROWS]);
+}
+
+#[test]
+fn falling_and_settled_cells_differ() {
+    let mut status = Status::new();
+    status.active.set(4, 0);
+    status.passive.set(0, 4);
+    let frame = frame(&status);
+
+    assert!(frame.contains("|\x1b[43m  \x1b[49m        |"));
+    assert!(frame.contains("|        \x1b[46m  \x1b[0m|"));
+}
+
+#[test]
+fn worst_case_fits_frame_capacity() {
+    let mut status = Status::new();
+    for row in 0..Board::ROWS {
+        for column in 0..Board::COLUMNS {
+            match (row + column) % 3 {
+                0 => status.active.set(row, column),
+                1 => status.passive.set(row, column),
+                _ => {}
+            }
+        }
+    }
+    status.score = Score {
+        points: u32::MAX,
+        rows: u32::MAX,
+        level: u8::MAX,
+        tiles: u32::MAX,
+    };
+    status.phase = Phase::TileFloating { column: 4 };
+    status.paused = true;
+
+    assert!(frame(&status).len() <= FRAME_CAPACITY);
+}
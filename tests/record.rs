@@ -0,0 +1,80 @@
+//! Host-side tests of [`Recording`].
+//! Run them via `cargo test-host` (see `.cargo/config.toml`).
+
+use microtile_app::game::{
+    record::{Entry, ImportError, Recording},
+    snapshot::Step,
+};
+
+fn entry(tick: u32, step: Step) -> Entry {
+    Entry { tick, step }
+}
+
+#[test]
+fn ticks_are_counted_rather_than_recorded() {
+    let mut recording = Recording::new();
+    recording.restart(Some(7));
+
+    recording.record(Step::Tick);
+    recording.record(Step::MoveTo(1));
+    recording.record(Step::Tick);
+    recording.record(Step::Tick);
+    recording.record(Step::Rotate);
+
+    assert_eq!(recording.ticks(), 3);
+    assert_eq!(
+        recording.entries().collect::<Vec<_>>(),
+        [entry(1, Step::MoveTo(1)), entry(3, Step::Rotate)]
+    );
+    assert!(recording.is_complete());
+}
+
+#[test]
+fn entries_print_as_imported() {
+    assert_eq!(entry(12, Step::Rotate).to_string(), "12 rotate");
+    assert_eq!(entry(0, Step::MoveTo(3)).to_string(), "0 column 3");
+}
+
+#[test]
+fn imported_entries_follow_each_other_within_the_ticks() {
+    let mut recording = Recording::new();
+    recording.restart(Some(7));
+    recording.set_ticks(10);
+
+    assert_eq!(recording.import(entry(2, Step::Rotate)), Ok(()));
+    assert_eq!(recording.import(entry(2, Step::MoveTo(0))), Ok(()));
+    assert_eq!(
+        recording.import(entry(1, Step::Rotate)),
+        Err(ImportError::OutOfOrder)
+    );
+    assert_eq!(
+        recording.import(entry(11, Step::Rotate)),
+        Err(ImportError::OutOfOrder)
+    );
+    assert_eq!(
+        recording.import(entry(5, Step::Tick)),
+        Err(ImportError::OutOfOrder)
+    );
+    assert_eq!(recording.import(entry(10, Step::Rotate)), Ok(()));
+
+    assert_eq!(recording.end(), 3);
+    assert_eq!(recording.ticks(), 10);
+    assert_eq!(recording.seed(), Some(7));
+    assert!(recording.is_complete());
+}
+
+#[test]
+fn import_does_not_overwrite_the_beginning() {
+    let mut recording = Recording::new();
+    recording.restart(None);
+    recording.set_ticks(1);
+
+    for _ in 0..Recording::CAPACITY {
+        assert_eq!(recording.import(entry(0, Step::Rotate)), Ok(()));
+    }
+    assert_eq!(
+        recording.import(entry(1, Step::Rotate)),
+        Err(ImportError::Full)
+    );
+    assert!(recording.is_complete());
+}
@@ -0,0 +1,54 @@
+//! Host-side tests of the scoring rules.
+
+use microtile_app::game::{board::Board, score::Score};
+
+#[test]
+fn clearing_rows_awards_points() {
+    let mut score = Score::new();
+
+    assert!(!score.add_rows(0));
+    assert_eq!(score, Score::new());
+
+    score.add_rows(1);
+    assert_eq!(score.points, 40);
+    score.add_rows(2);
+    assert_eq!(score.points, 140);
+    assert_eq!(score.rows, 3);
+}
+
+#[test]
+fn points_scale_with_level() {
+    let mut score = Score::new();
+
+    assert!(score.add_rows(Score::ROWS_PER_LEVEL));
+    assert_eq!(score.level, 1);
+
+    let points = score.points;
+    score.add_rows(1);
+    assert_eq!(score.points - points, 2 * 40);
+}
+
+#[test]
+fn level_is_capped() {
+    let mut score = Score::new();
+
+    for _ in 0..=u32::from(Score::MAX_LEVEL) {
+        score.add_rows(Score::ROWS_PER_LEVEL);
+    }
+    assert_eq!(score.level, Score::MAX_LEVEL);
+    assert!(!score.add_rows(Score::ROWS_PER_LEVEL));
+}
+
+#[test]
+fn board_detects_full_rows() {
+    let mut board = Board::empty();
+    for column in 0..Board::COLUMNS {
+        board.set(1, column);
+    }
+    board.set(0, 0);
+    board.set(Board::ROWS, 0);
+
+    assert_eq!(board.full_rows(), 1);
+    assert_eq!(board.count(), 6);
+    assert!(!board.is_set(Board::ROWS, 0));
+}
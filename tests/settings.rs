@@ -0,0 +1,279 @@
+//! Host-side tests of the settings and their record format.
+//! Run them via `cargo test-host` (see `.cargo/config.toml`).
+
+use microtile_app::{
+    game::{
+        shading::{DisplayStyle, Shading},
+        speed::Speed,
+        tilt::TiltSettings,
+    },
+    settings::{
+        store::{crc32, record_size, Flash, Record, Store},
+        Key, OutOfRange, Settings,
+    },
+};
+
+const PAGE_SIZE: usize = 256;
+const RECORD_SIZE: usize = record_size(Settings::ENCODED_SIZE);
+
+/// Two pages of NOR flash in RAM, checking that the store only programs erased bytes.
+#[derive(Debug, Clone)]
+struct RamFlash {
+    pages: [[u8; PAGE_SIZE]; 2],
+    erases: [usize; 2],
+}
+
+impl RamFlash {
+    fn new() -> Self {
+        Self {
+            pages: [[0xff; PAGE_SIZE]; 2],
+            erases: [0; 2],
+        }
+    }
+}
+
+impl Flash for RamFlash {
+    type Error = ();
+
+    const PAGE_SIZE: usize = PAGE_SIZE;
+
+    fn read(&mut self, page: usize, offset: usize, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        let stored = self.pages.get(page).ok_or(())?;
+        bytes.copy_from_slice(stored.get(offset..offset + bytes.len()).ok_or(())?);
+        Ok(())
+    }
+
+    fn write(&mut self, page: usize, offset: usize, bytes: &[u8]) -> Result<(), Self::Error> {
+        assert_eq!(offset % 4, 0, "unaligned write");
+        assert_eq!(bytes.len() % 4, 0, "unaligned length");
+        let stored = self.pages.get_mut(page).ok_or(())?;
+        for (stored, byte) in stored[offset..offset + bytes.len()].iter_mut().zip(bytes) {
+            assert_eq!(*stored, 0xff, "writing to flash that has not been erased");
+            *stored = *byte;
+        }
+        Ok(())
+    }
+
+    fn erase(&mut self, page: usize) -> Result<(), Self::Error> {
+        *self.pages.get_mut(page).ok_or(())? = [0xff; PAGE_SIZE];
+        self.erases[page] += 1;
+        Ok(())
+    }
+}
+
+fn open(flash: &mut RamFlash) -> Store<Settings> {
+    Store::open(flash, 0).unwrap()
+}
+
+fn encode(settings: &Settings) -> [u8; Settings::ENCODED_SIZE] {
+    let mut bytes = [0; Settings::ENCODED_SIZE];
+    settings.encode(&mut bytes);
+    bytes
+}
+
+fn settings(seed: u32) -> Settings {
+    Settings {
+        speed: Speed {
+            period_ms: 700,
+            soft_drop: 5,
+        },
+        tilt: TiltSettings {
+            smoothing: 50,
+            hysteresis: 1,
+        },
+        calibration: Some((-120, 1013)),
+        seed,
+        shading: Shading {
+            style: DisplayStyle::Both,
+            settled: 7,
+            falling: 2,
+        },
+    }
+}
+
+#[test]
+fn crc_matches_the_reference() {
+    assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    assert_eq!(crc32(&[]), 0);
+}
+
+#[test]
+fn settings_are_encoded_losslessly() {
+    let settings = settings(0xdead_beef);
+    assert_eq!(
+        Settings::decode(Settings::VERSION, &encode(&settings)),
+        Some(settings)
+    );
+    assert_eq!(
+        Settings::decode(Settings::VERSION, &encode(&Settings::DEFAULT)),
+        Some(Settings::DEFAULT)
+    );
+}
+
+#[test]
+fn unknown_versions_and_invalid_values_are_not_decoded() {
+    let bytes = encode(&settings(1));
+    assert_eq!(Settings::decode(Settings::VERSION + 1, &bytes), None);
+    assert_eq!(Settings::decode(Settings::VERSION, &bytes[1..]), None);
+
+    let mut invalid = bytes;
+    invalid[2] = 0; // soft drop multiplier
+    assert_eq!(Settings::decode(Settings::VERSION, &invalid), None);
+
+    let mut invalid_style = bytes;
+    invalid_style[14] = 3;
+    assert_eq!(Settings::decode(Settings::VERSION, &invalid_style), None);
+
+    let mut invalid_brightness = bytes;
+    invalid_brightness[15] = 0x0a;
+    assert_eq!(
+        Settings::decode(Settings::VERSION, &invalid_brightness),
+        None
+    );
+}
+
+#[test]
+fn settings_predating_the_shading_are_decoded() {
+    let mut bytes = encode(&settings(1));
+    bytes[14..].fill(0);
+    assert_eq!(
+        Settings::decode(1, &bytes),
+        Some(Settings {
+            shading: Shading::DEFAULT,
+            ..settings(1)
+        })
+    );
+}
+
+#[test]
+fn values_are_checked_against_their_range() {
+    let mut settings = Settings::DEFAULT;
+    assert_eq!(settings.set(Key::SpeedPeriod, 99), Err(OutOfRange));
+    assert_eq!(settings.set(Key::TiltHysteresis, 6), Err(OutOfRange));
+    assert_eq!(settings, Settings::DEFAULT);
+
+    assert_eq!(settings.set(Key::SpeedPeriod, 250), Ok(()));
+    assert_eq!(settings.set(Key::TiltSmoothing, 100), Ok(()));
+    assert_eq!(settings.get(Key::SpeedPeriod), 250);
+    assert_eq!(settings.speed.period_ms, 250);
+    assert_eq!(settings.get(Key::TiltSmoothing), 100);
+    for key in Key::ALL {
+        assert!(key.range().contains(&Settings::DEFAULT.get(key)));
+    }
+}
+
+#[test]
+fn defaults_keep_the_calibration() {
+    let mut settings = settings(42);
+    settings.reset();
+    assert_eq!(
+        settings,
+        Settings {
+            calibration: Some((-120, 1013)),
+            ..Settings::DEFAULT
+        }
+    );
+}
+
+#[test]
+fn empty_flash_holds_no_settings() {
+    let mut flash = RamFlash::new();
+    let store = open(&mut flash);
+    assert_eq!(store.get().copied(), None);
+}
+
+#[test]
+fn saved_settings_are_restored() {
+    let mut flash = RamFlash::new();
+    let mut store = open(&mut flash);
+    store.save(&mut flash, settings(1)).unwrap();
+    assert_eq!(store.get().copied(), Some(settings(1)));
+
+    let store = open(&mut flash);
+    assert_eq!(store.get().copied(), Some(settings(1)));
+}
+
+#[test]
+fn the_newest_record_wins() {
+    let mut flash = RamFlash::new();
+    let mut store = open(&mut flash);
+    for seed in 1..=3 {
+        store.save(&mut flash, settings(seed)).unwrap();
+    }
+
+    let mut store = open(&mut flash);
+    assert_eq!(store.get().copied(), Some(settings(3)));
+    // appends after the newest record rather than overwriting it
+    store.save(&mut flash, settings(4)).unwrap();
+    let store = open(&mut flash);
+    assert_eq!(store.get().copied(), Some(settings(4)));
+}
+
+#[test]
+fn pages_are_erased_in_turn() {
+    let per_page = PAGE_SIZE / RECORD_SIZE;
+    let mut flash = RamFlash::new();
+    let mut store = open(&mut flash);
+    let mut seed = 0;
+    for _ in 0..4 * per_page {
+        seed += 1;
+        store.save(&mut flash, settings(seed)).unwrap();
+    }
+    // The first save erases page 0, each page filling up erases the other one.
+    assert_eq!(flash.erases, [2, 2]);
+
+    for _ in 0..per_page {
+        let mut store = open(&mut flash);
+        assert_eq!(store.get().copied(), Some(settings(seed)));
+        seed += 1;
+        store.save(&mut flash, settings(seed)).unwrap();
+    }
+    assert_eq!(flash.erases, [3, 2]);
+    let store = open(&mut flash);
+    assert_eq!(store.get().copied(), Some(settings(seed)));
+}
+
+#[test]
+fn torn_records_are_skipped() {
+    let mut flash = RamFlash::new();
+    let mut store = open(&mut flash);
+    store.save(&mut flash, settings(1)).unwrap();
+    store.save(&mut flash, settings(2)).unwrap();
+
+    // a reset while writing the second record leaves part of it erased
+    flash.pages[0][RECORD_SIZE + RECORD_SIZE / 2..2 * RECORD_SIZE].fill(0xff);
+    let mut store = open(&mut flash);
+    assert_eq!(store.get().copied(), Some(settings(1)));
+
+    // the next record goes after the torn one
+    store.save(&mut flash, settings(3)).unwrap();
+    let store = open(&mut flash);
+    assert_eq!(store.get().copied(), Some(settings(3)));
+}
+
+#[test]
+fn corrupted_records_are_skipped() {
+    let mut flash = RamFlash::new();
+    let mut store = open(&mut flash);
+    store.save(&mut flash, settings(1)).unwrap();
+    store.save(&mut flash, settings(2)).unwrap();
+
+    flash.pages[0][RECORD_SIZE + 14] ^= 0x01;
+    let store = open(&mut flash);
+    assert_eq!(store.get().copied(), Some(settings(1)));
+}
+
+#[test]
+fn foreign_data_is_left_alone() {
+    let mut flash = RamFlash::new();
+    // e.g. the tilt calibration stored by earlier firmware
+    flash.pages[1][..4].copy_from_slice(b"TILT");
+    let mut store = open(&mut flash);
+    assert_eq!(store.get().copied(), None);
+
+    store.save(&mut flash, settings(1)).unwrap();
+    assert_eq!(&flash.pages[1][..4], b"TILT");
+    assert_eq!(flash.erases, [1, 0]);
+    let store = open(&mut flash);
+    assert_eq!(store.get().copied(), Some(settings(1)));
+}
@@ -0,0 +1,62 @@
+//! Host-side tests of the shading telling the falling tile apart from the settled cells.
+//! Run them via `cargo test-host` (see `.cargo/config.toml`).
+
+use microtile_app::game::{
+    board::Board,
+    shading::{DisplayStyle, ShadedBoard, Shading},
+};
+
+fn shading(style: DisplayStyle) -> Shading {
+    Shading {
+        style,
+        settled: 9,
+        falling: 4,
+    }
+}
+
+fn boards() -> (Board, Board) {
+    let mut active = Board::empty();
+    active.set(4, 2);
+    let mut passive = Board::empty();
+    passive.set(0, 0);
+    (active, passive)
+}
+
+#[test]
+fn dimmed_tile_stays_lit() {
+    let shading = shading(DisplayStyle::Dim);
+    assert_eq!(shading.falling_brightness(true), 4);
+    assert_eq!(shading.falling_brightness(false), 4);
+}
+
+#[test]
+fn blinking_tile_is_hidden_every_other_frame() {
+    let blink = shading(DisplayStyle::Blink);
+    assert_eq!(blink.falling_brightness(true), 9);
+    assert_eq!(blink.falling_brightness(false), 0);
+
+    let both = shading(DisplayStyle::Both);
+    assert_eq!(both.falling_brightness(true), 4);
+    assert_eq!(both.falling_brightness(false), 0);
+}
+
+#[test]
+fn cells_are_shaded_by_layer() {
+    let (active, passive) = boards();
+    let board = ShadedBoard::new(active, passive, shading(DisplayStyle::Dim), true);
+    assert_eq!(board.brightness(0, 0), 9);
+    assert_eq!(board.brightness(4, 2), 4);
+    assert_eq!(board.brightness(2, 2), 0);
+    assert_eq!(board.brightness(Board::ROWS, 0), 0);
+
+    let unlit = ShadedBoard::new(active, passive, shading(DisplayStyle::Both), false);
+    assert_eq!(unlit.brightness(0, 0), 9);
+    assert_eq!(unlit.brightness(4, 2), 0);
+}
+
+#[test]
+fn settled_cells_win_over_the_falling_tile() {
+    let (_, passive) = boards();
+    let board = ShadedBoard::new(passive, passive, shading(DisplayStyle::Dim), true);
+    assert_eq!(board.brightness(0, 0), 9);
+}
@@ -0,0 +1,163 @@
+//! Host-side tests of the game snapshots' journal and record format.
+//! Run them via `cargo test-host` (see `.cargo/config.toml`).
+
+use microtile_app::{
+    game::{
+        board::Board,
+        score::Score,
+        snapshot::{Journal, JournalFull, Snapshot, Step},
+    },
+    settings::store::Record,
+};
+
+fn journal(steps: &[Step]) -> Journal {
+    let mut journal = Journal::new();
+    for step in steps {
+        journal.push(*step).unwrap();
+    }
+    journal
+}
+
+fn snapshot(steps: &[Step]) -> Snapshot {
+    let mut active = Board::empty();
+    active.set(4, 1);
+    active.set(4, 2);
+    let mut passive = Board::empty();
+    passive.set(0, 0);
+    passive.set(0, 4);
+    Snapshot {
+        seed: 0xdead_beef,
+        score: Score {
+            points: 1200,
+            rows: 7,
+            level: 1,
+            tiles: 23,
+        },
+        play_secs: 95,
+        active,
+        passive,
+        journal: journal(steps),
+    }
+}
+
+fn encode(snapshot: &Option<Snapshot>) -> Vec<u8> {
+    let mut bytes = vec![0; <Option<Snapshot>>::ENCODED_SIZE];
+    snapshot.encode(&mut bytes);
+    bytes
+}
+
+fn decode(bytes: &[u8]) -> Option<Option<Snapshot>> {
+    <Option<Snapshot>>::decode(<Option<Snapshot>>::VERSION, bytes)
+}
+
+#[test]
+fn journal_keeps_steps_in_order() {
+    let steps = [
+        Step::Tick,
+        Step::MoveTo(0),
+        Step::Rotate,
+        Step::Tick,
+        Step::MoveTo(4),
+        Step::Tick,
+    ];
+    let journal = journal(&steps);
+
+    assert_eq!(journal.steps().collect::<Vec<_>>(), steps);
+    assert_eq!(journal.len(), steps.len());
+}
+
+#[test]
+fn journal_packs_runs_of_ticks() {
+    let steps = [Step::Tick; 20];
+    let journal = journal(&steps);
+
+    assert_eq!(journal.steps().collect::<Vec<_>>(), steps);
+    assert_eq!(journal.len(), 3);
+}
+
+#[test]
+fn full_journal_rejects_steps() {
+    let mut journal = Journal::new();
+    for _ in 0..Journal::CAPACITY {
+        journal.push(Step::Rotate).unwrap();
+    }
+
+    assert_eq!(journal.push(Step::MoveTo(1)), Err(JournalFull));
+    // ticks only fit into a run of ticks, which the last step is not
+    assert_eq!(journal.push(Step::Tick), Err(JournalFull));
+    assert_eq!(journal.len(), Journal::CAPACITY);
+}
+
+#[test]
+fn cleared_journal_equals_new_one() {
+    let mut journal = journal(&[Step::Rotate, Step::Tick]);
+    journal.clear();
+
+    assert!(journal.is_empty());
+    assert_eq!(journal, Journal::new());
+}
+
+#[test]
+fn snapshots_are_encoded_losslessly() {
+    let snapshot = Some(snapshot(&[
+        Step::Tick,
+        Step::MoveTo(3),
+        Step::Rotate,
+        Step::Tick,
+    ]));
+    assert_eq!(decode(&encode(&snapshot)), Some(snapshot));
+
+    let empty = Some(self::snapshot(&[]));
+    assert_eq!(decode(&encode(&empty)), Some(empty));
+
+    assert_eq!(decode(&encode(&None)), Some(None));
+}
+
+#[test]
+fn full_journals_are_encoded_losslessly() {
+    let mut snapshot = snapshot(&[]);
+    for column in (0..5).cycle().take(Journal::CAPACITY) {
+        snapshot.journal.push(Step::MoveTo(column)).unwrap();
+    }
+    let snapshot = Some(snapshot);
+
+    assert_eq!(decode(&encode(&snapshot)), Some(snapshot));
+}
+
+#[test]
+fn corrupted_snapshots_are_not_decoded() {
+    let bytes = encode(&Some(snapshot(&[Step::Rotate, Step::Tick])));
+    assert_eq!(
+        <Option<Snapshot>>::decode(<Option<Snapshot>>::VERSION + 1, &bytes),
+        None
+    );
+    assert_eq!(decode(&bytes[1..]), None);
+
+    let mut invalid_flag = bytes.clone();
+    invalid_flag[0] = 2;
+    assert_eq!(decode(&invalid_flag), None);
+
+    let mut invalid_level = bytes.clone();
+    invalid_level[1] = Score::MAX_LEVEL + 1;
+    assert_eq!(decode(&invalid_level), None);
+
+    let mut invalid_board = bytes.clone();
+    invalid_board[31] = 0x80;
+    assert_eq!(decode(&invalid_board), None);
+
+    let mut invalid_step = bytes.clone();
+    invalid_step[32] = 0x0f;
+    assert_eq!(decode(&invalid_step), None);
+
+    // steps beyond the journal's length
+    let mut trailing_step = bytes;
+    trailing_step[33] = 0x01;
+    assert_eq!(decode(&trailing_step), None);
+}
+
+#[test]
+fn boards_are_converted_to_bits_and_back() {
+    let board = snapshot(&[]).passive;
+    assert_eq!(Board::from_bits(board.bits()), Some(board));
+    assert_eq!(Board::from_bits(1 << 25), None);
+}
@@ -0,0 +1,46 @@
+//! Host-side tests of [`Speed`].
+//!
+//! Run them via `cargo test-host` (see `.cargo/config.toml`).
+
+use microtile_app::game::speed::Speed;
+
+#[test]
+fn levels_speed_up_ticks() {
+    let speed = Speed::DEFAULT;
+    assert_eq!(speed.period_us(0), 1_000_000);
+    assert_eq!(speed.period_us(3), 500_000);
+    assert!(speed.period_us(9) < speed.period_us(8));
+}
+
+#[test]
+fn soft_drop_divides_period() {
+    let speed = Speed {
+        period_ms: 600,
+        soft_drop: 4,
+    };
+    assert_eq!(speed.soft_drop_period_us(0), 150_000);
+    assert_eq!(speed.soft_drop_period_us(3), 75_000);
+}
+
+#[test]
+fn zero_soft_drop_does_not_speed_up() {
+    let speed = Speed {
+        period_ms: 600,
+        soft_drop: 0,
+    };
+    assert_eq!(speed.soft_drop_period_us(0), speed.period_us(0));
+}
+
+#[test]
+fn extreme_speeds_do_not_overflow() {
+    let slowest = Speed {
+        period_ms: u16::MAX,
+        soft_drop: 1,
+    };
+    assert!(slowest.period_us(0) > 0);
+    let fastest = Speed {
+        period_ms: *Speed::PERIODS.start(),
+        soft_drop: *Speed::SOFT_DROPS.end(),
+    };
+    assert!(fastest.soft_drop_period_us(u8::MAX) > 0);
+}
@@ -0,0 +1,128 @@
+//! Host-side tests of the [`TileProducer`]s.
+//!
+//! Run them via `cargo test-host` (see `.cargo/config.toml`).
+
+use microtile_app::game::tile::{
+    Lookahead, RandomProducer, TileIterator, TileKind, TileProducer, BAG_SIZE,
+};
+use microtile_engine::geometry::tile::BasicTile;
+
+/// Index of `tile`, to compare and count tiles easily.
+fn kind(tile: &BasicTile) -> usize {
+    match tile {
+        BasicTile::Square => 0,
+        BasicTile::Line => 1,
+        BasicTile::Diagonal => 2,
+    }
+}
+
+fn sequence<P>(producer: P, length: usize) -> Vec<usize>
+where
+    P: TileProducer,
+{
+    TileIterator::new(producer)
+        .take(length)
+        .map(|tile| kind(&tile))
+        .collect()
+}
+
+#[test]
+fn same_seed_yields_same_sequence() {
+    assert_eq!(
+        sequence(RandomProducer::new(42), 100),
+        sequence(RandomProducer::new(42), 100)
+    );
+}
+
+#[test]
+fn different_seeds_yield_different_sequences() {
+    assert_ne!(
+        sequence(RandomProducer::new(1), 100),
+        sequence(RandomProducer::new(2), 100)
+    );
+}
+
+#[test]
+fn every_bag_holds_every_tile() {
+    for seed in 0..100 {
+        let tiles = sequence(RandomProducer::new(seed), 10 * BAG_SIZE);
+        for bag in tiles.chunks(BAG_SIZE) {
+            let mut bag = bag.to_vec();
+            bag.sort_unstable();
+            assert_eq!(bag, [0, 1, 2], "seed {seed}");
+        }
+    }
+}
+
+#[test]
+fn no_tile_is_starved() {
+    let tiles = sequence(RandomProducer::new(7), 1000);
+    for tile in 0..3 {
+        let positions: Vec<_> = tiles
+            .iter()
+            .enumerate()
+            .filter_map(|(i, t)| (*t == tile).then_some(i))
+            .collect();
+        assert!(positions[0] < BAG_SIZE);
+        assert!(positions.windows(2).all(|w| w[1] - w[0] < 2 * BAG_SIZE));
+    }
+}
+
+#[test]
+fn new_game_can_be_replayed_from_its_seed() {
+    let mut producer = RandomProducer::new(42);
+    assert_eq!(producer.seed(), Some(42));
+    for _ in 0..5 {
+        producer.generate_tile();
+    }
+
+    producer.start_game();
+    let seed = producer.seed().expect("producer should be seedable");
+    assert_ne!(seed, 42);
+
+    assert_eq!(
+        sequence(producer, 100),
+        sequence(RandomProducer::new(seed), 100)
+    );
+}
+
+#[test]
+fn lookahead_does_not_alter_sequence() {
+    assert_eq!(
+        sequence(Lookahead::<_, 3>::new(RandomProducer::new(42)), 100),
+        sequence(RandomProducer::new(42), 100)
+    );
+}
+
+#[test]
+fn lookahead_peeks_at_next_tile() {
+    let mut lookahead = Lookahead::<_, 1>::new(RandomProducer::new(42));
+    for _ in 0..20 {
+        let next = lookahead.peek().map(TileKind::from);
+        assert_eq!(next, Some(TileKind::from(&lookahead.generate_tile())));
+    }
+}
+
+#[test]
+fn lookahead_lists_upcoming_tiles() {
+    let mut lookahead = Lookahead::<_, 3>::new(RandomProducer::new(42));
+    lookahead.generate_tile();
+
+    let upcoming: Vec<_> = lookahead.upcoming().map(kind).collect();
+    let generated = sequence(lookahead, 3);
+    assert_eq!(upcoming, generated);
+}
+
+#[test]
+fn lookahead_starts_game_afresh() {
+    let mut lookahead = Lookahead::<_, 2>::new(RandomProducer::new(42));
+    lookahead.generate_tile();
+
+    lookahead.start_game();
+    let seed = lookahead.seed().expect("producer should be seedable");
+
+    assert_eq!(
+        sequence(lookahead, 100),
+        sequence(RandomProducer::new(seed), 100)
+    );
+}
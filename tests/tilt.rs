@@ -0,0 +1,236 @@
+//! Host-side tests of the conversion of accelerometer samples into columns.
+//!
+//! The traces are `(x, z)` samples as reported by the accelerometer at 25 Hz.
+//!
+//! Run them via `cargo test-host` (see `.cargo/config.toml`).
+
+use core::f32::consts::{FRAC_PI_2, PI};
+use microtile_app::game::{
+    message::Message,
+    tilt::{tilt_towards, Calibrator, ColumnSelector, Tilt},
+};
+
+/// Board held still right at the boundary between columns 1 and 2.
+const RESTING_ON_BOUNDARY: [(i16, i16); 24] = [
+    (-221, -964),
+    (-220, -985),
+    (-238, -982),
+    (-187, -966),
+    (-189, -970),
+    (-205, -972),
+    (-256, -955),
+    (-202, -964),
+    (-257, -1020),
+    (-237, -988),
+    (-207, -978),
+    (-202, -993),
+    (-207, -967),
+    (-231, -934),
+    (-201, -947),
+    (-230, -995),
+    (-223, -979),
+    (-199, -970),
+    (-226, -1001),
+    (-228, -946),
+    (-235, -971),
+    (-204, -1014),
+    (-213, -944),
+    (-265, -985),
+];
+
+/// Board tilted steadily from column 0 all the way to column 4, then held there.
+const SWEEP: [(i16, i16); 40] = [
+    (-709, -719),
+    (-660, -745),
+    (-648, -767),
+    (-573, -798),
+    (-517, -837),
+    (-490, -890),
+    (-435, -905),
+    (-402, -938),
+    (-359, -947),
+    (-274, -986),
+    (-263, -967),
+    (-167, -973),
+    (-163, -1029),
+    (-76, -1008),
+    (-44, -985),
+    (44, -997),
+    (85, -990),
+    (159, -982),
+    (196, -974),
+    (218, -951),
+    (308, -948),
+    (315, -948),
+    (408, -946),
+    (442, -881),
+    (472, -846),
+    (547, -845),
+    (588, -802),
+    (628, -762),
+    (658, -751),
+    (723, -707),
+    (694, -693),
+    (729, -714),
+    (686, -709),
+    (705, -712),
+    (728, -723),
+    (726, -726),
+    (695, -698),
+    (724, -694),
+    (712, -705),
+    (709, -698),
+];
+
+/// Board held in column 2, disturbed by a single bump.
+const SPIKE: [(i16, i16); 17] = [
+    (-2, -997),
+    (6, -1000),
+    (8, -994),
+    (20, -997),
+    (-4, -1004),
+    (0, -991),
+    (-3, -996),
+    (18, -1026),
+    (696, -705),
+    (4, -998),
+    (-4, -993),
+    (3, -1005),
+    (24, -996),
+    (-6, -1001),
+    (-2, -1001),
+    (-27, -1005),
+    (10, -1012),
+];
+
+/// Angle between columns 1 and 2.
+const BOUNDARY_1_2: f32 = -FRAC_PI_2 - PI / 16.0;
+
+fn raw_columns(trace: &[(i16, i16)]) -> Vec<u8> {
+    trace
+        .iter()
+        .map(|(x, z)| ColumnSelector::new(0.0).column_at(f32::from(*z).atan2(f32::from(*x))))
+        .collect()
+}
+
+/// Sample of the board inclined by `angle` (as in `z.atan2(x)`).
+fn sample(angle: f32) -> (i16, i16) {
+    #[allow(clippy::cast_possible_truncation)]
+    let sample = ((1000.0 * angle.cos()) as i16, (1000.0 * angle.sin()) as i16);
+    sample
+}
+
+fn columns(tilt: &mut Tilt, trace: &[(i16, i16)]) -> Vec<u8> {
+    trace.iter().map(|(x, z)| tilt.column(*x, *z)).collect()
+}
+
+#[test]
+fn noise_on_boundary_does_not_jitter() {
+    let raw = raw_columns(&RESTING_ON_BOUNDARY);
+    assert!(
+        raw.contains(&1) && raw.contains(&2),
+        "trace should be noisy"
+    );
+
+    let columns = columns(&mut Tilt::default(), &RESTING_ON_BOUNDARY);
+    assert!(columns.iter().all(|column| *column == columns[0]));
+}
+
+#[test]
+fn sweep_passes_every_column_once() {
+    let columns = columns(&mut Tilt::default(), &SWEEP);
+
+    assert_eq!(columns.first(), Some(&0));
+    assert_eq!(columns.last(), Some(&4));
+    assert!(columns.windows(2).all(|w| w[0] <= w[1] && w[1] - w[0] <= 1));
+}
+
+#[test]
+fn spike_is_smoothed_away() {
+    let columns = columns(&mut Tilt::default(), &SPIKE);
+    assert!(columns.iter().all(|column| *column == 2));
+}
+
+#[test]
+fn disabled_filter_matches_raw_columns() {
+    for trace in [&RESTING_ON_BOUNDARY[..], &SWEEP[..], &SPIKE[..]] {
+        assert_eq!(columns(&mut Tilt::new(1.0, 0.0), trace), raw_columns(trace));
+    }
+}
+
+#[test]
+fn reset_forgets_previous_samples() {
+    let mut tilt = Tilt::default();
+    columns(&mut tilt, &SWEEP);
+
+    tilt.reset();
+    assert_eq!(tilt.column(SWEEP[0].0, SWEEP[0].1), 0);
+}
+
+#[test]
+fn synthetic_samples_select_their_column() {
+    let mut tilt = Tilt::default();
+    for column in [0, 1, 2, 3, 4, 2] {
+        let Message::AccelerometerData { x, z } = tilt_towards(column) else {
+            panic!("tilting should yield an accelerometer sample");
+        };
+        tilt.reset();
+        assert_eq!(tilt.column(x, z), column);
+    }
+}
+
+#[test]
+fn selector_sticks_to_column_within_band() {
+    let band = PI / 32.0;
+    let mut selector = ColumnSelector::new(band);
+    assert_eq!(selector.select(BOUNDARY_1_2 - band), 1);
+
+    assert_eq!(selector.select(BOUNDARY_1_2 + band / 2.0), 1);
+    assert_eq!(selector.select(BOUNDARY_1_2 + band * 1.5), 2);
+    assert_eq!(selector.select(BOUNDARY_1_2 - band / 2.0), 2);
+    assert_eq!(selector.select(BOUNDARY_1_2 - band * 1.5), 1);
+}
+
+#[test]
+fn selector_jumps_several_columns() {
+    let mut selector = ColumnSelector::new(PI / 32.0);
+    assert_eq!(selector.select(-FRAC_PI_2 - PI / 4.0), 0);
+    assert_eq!(selector.select(-FRAC_PI_2 + PI / 4.0), 4);
+}
+
+#[test]
+fn calibration_centers_columns_around_neutral() {
+    // held one column to the right
+    let neutral = ColumnSelector::LEVEL + PI / 8.0;
+    let (x, z) = sample(neutral);
+    let mut tilt = Tilt::new(1.0, 0.0);
+    assert_eq!(tilt.column(x, z), 3);
+
+    assert!(tilt.calibrate(x, z));
+    assert_eq!(tilt.column(x, z), 2);
+    let (x, z) = sample(ColumnSelector::LEVEL);
+    assert_eq!(tilt.column(x, z), 1);
+}
+
+#[test]
+fn calibration_rejects_implausible_neutral() {
+    let mut tilt = Tilt::new(1.0, 0.0);
+    let (x, z) = sample(ColumnSelector::LEVEL);
+
+    assert!(!tilt.calibrate(0, 0));
+    let (ux, uz) = sample(0.0);
+    assert!(!tilt.calibrate(ux, uz));
+    assert_eq!(tilt.column(x, z), 2);
+}
+
+#[test]
+fn calibrator_averages_samples() {
+    let mut calibrator = Calibrator::new();
+    for _ in 1..Calibrator::SAMPLES / 2 {
+        assert_eq!(calibrator.add(-100, -1000), None);
+        assert_eq!(calibrator.add(100, -1000), None);
+    }
+    assert_eq!(calibrator.add(-100, -1000), None);
+    assert_eq!(calibrator.add(100, -1000), None);
+    assert_eq!(calibrator.add(0, -1000), Some((0, -1000)));
+}